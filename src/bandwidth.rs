@@ -1,84 +1,479 @@
 use crate::{
     Result,
     error::CryptoNodeError,
-    types::{BandwidthMetrics, CurrencyType},
-    wallet::WalletManager,
+    tasks,
+    types::{BandwidthMetrics, CurrencyType, RoundingMode},
+    wallet::{BalanceChangeReason, WalletEvent, WalletManager},
 };
-use std::sync::Arc;
-use tokio::sync::RwLock;
-use tokio::time::{Duration, interval};
+use futures::future::BoxFuture;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::{RwLock, broadcast, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant, interval};
 use uuid::Uuid;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+/// Default floor for the measurement interval
+///
+/// Each interval may query external services to price rewards, and very short
+/// intervals produce noisy micro-rewards, so intervals below this are refused.
+pub const DEFAULT_MIN_MEASUREMENT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Weight of the newest interval in `BandwidthMetrics::smoothed_rate`
+const RATE_SMOOTHING: f64 = 0.3;
+
+/// Default number of payouts kept in the reward ledger
+pub const DEFAULT_REWARD_LEDGER_CAPACITY: usize = 10_000;
+
+/// Measures bandwidth shared by this node
+///
+/// `measure` is async, boxed so meters can be used as trait objects;
+/// implementations return `Box::pin(async move { ... })`.
+pub trait BandwidthMeter: Send + Sync {
+    /// Bytes shared since the previous call
+    fn measure(&self) -> BoxFuture<'_, Result<u64>>;
+}
+
+/// Meter reading the byte counters of the host's network interfaces
+///
+/// Counts bytes received and transmitted on every interface except loopback.
+/// Only Linux (`/proc/net/dev`) is supported; `new` fails elsewhere.
+pub struct InterfaceMeter {
+    last_total: Mutex<u64>,
+}
+
+impl InterfaceMeter {
+    /// Create a meter, taking the current counters as the baseline
+    pub async fn new() -> Result<Self> {
+        Ok(Self {
+            last_total: Mutex::new(interface_byte_total().await?),
+        })
+    }
+}
+
+impl BandwidthMeter for InterfaceMeter {
+    fn measure(&self) -> BoxFuture<'_, Result<u64>> {
+        Box::pin(async move {
+            let total = interface_byte_total().await?;
+            let mut last_total = self.last_total.lock().unwrap_or_else(|e| e.into_inner());
+            // Counters reset when an interface goes away, so a drop counts as nothing shared
+            let delta = total.saturating_sub(*last_total);
+            *last_total = total;
+            Ok(delta)
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+async fn interface_byte_total() -> Result<u64> {
+    let contents = tokio::fs::read_to_string("/proc/net/dev").await
+        .map_err(|e| CryptoNodeError::Device(format!("Failed to read interface counters: {}", e)))?;
+    Ok(parse_proc_net_dev(&contents))
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn interface_byte_total() -> Result<u64> {
+    Err(CryptoNodeError::Device("Interface byte counters are only supported on Linux".to_string()))
+}
+
+/// Sum received and transmitted bytes over non-loopback interfaces
+///
+/// After two header lines, each line is `name: rx_bytes ... tx_bytes ...`
+/// with transmitted bytes as the ninth counter.
+#[cfg(target_os = "linux")]
+fn parse_proc_net_dev(contents: &str) -> u64 {
+    contents.lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (name, counters) = line.split_once(':')?;
+            if name.trim() == "lo" {
+                return None;
+            }
+            let counters: Vec<u64> = counters.split_whitespace()
+                .map(|c| c.parse().ok())
+                .collect::<Option<_>>()?;
+            Some(counters.first()?.wrapping_add(*counters.get(8)?))
+        })
+        .fold(0, u64::wrapping_add)
+}
+
+/// Simulated meter reporting between 1 MiB and 10 MiB per minute at random
+///
+/// For tests and hosts without interface counters; rewards it earns are
+/// meaningless.
+pub struct MockMeter {
+    last_measured: Mutex<Instant>,
+    rng: SystemRandom,
+}
+
+impl MockMeter {
+    pub fn new() -> Self {
+        Self {
+            last_measured: Mutex::new(Instant::now()),
+            rng: SystemRandom::new(),
+        }
+    }
+}
+
+impl Default for MockMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BandwidthMeter for MockMeter {
+    fn measure(&self) -> BoxFuture<'_, Result<u64>> {
+        Box::pin(async move {
+            let now = Instant::now();
+            let elapsed = {
+                let mut last_measured = self.last_measured.lock().unwrap_or_else(|e| e.into_inner());
+                now.duration_since(std::mem::replace(&mut *last_measured, now))
+            };
+
+            let mut sample = [0u8; 8];
+            self.rng.fill(&mut sample)
+                .map_err(|_| CryptoNodeError::CryptoOperation("Failed to generate random sample".to_string()))?;
+            let bytes_per_minute = 1_048_576 + u64::from_le_bytes(sample) % (10_485_760 - 1_048_576);
+            Ok((bytes_per_minute as f64 * elapsed.as_secs_f64() / 60.0) as u64)
+        })
+    }
+}
+
+/// A single reward payout and the bandwidth that earned it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewardRecord {
+    pub timestamp: DateTime<Utc>,
+    pub wallet_id: Uuid,
+    pub currency: CurrencyType,
+    pub bytes: u64, // Rewarded bytes since the previous payout
+    pub rate_used: f64,
+    pub amount: f64,
+}
+
+/// Events emitted by the bandwidth manager
+#[derive(Debug, Clone, PartialEq)]
+pub enum RewardEvent {
+    /// A reward was credited to a wallet
+    Paid(RewardRecord),
+}
+
+/// Bounded audit trail of reward payouts, oldest first
+struct RewardLedger {
+    records: VecDeque<RewardRecord>,
+    capacity: usize,
+}
+
+/// Reward a monitor has earned but not yet paid out
+#[derive(Debug, Default)]
+struct PendingReward {
+    amount: f64,
+    bytes: u64, // Rewarded bytes behind `amount`
+}
+
+impl RewardLedger {
+    fn record(&mut self, record: RewardRecord) {
+        self.records.push_back(record);
+        while self.records.len() > self.capacity {
+            self.records.pop_front();
+        }
+    }
+}
+
+/// Status of a wallet's bandwidth monitor
+#[derive(Debug, Clone)]
+pub struct MonitorInfo {
+    pub wallet_id: Uuid,
+    pub running: bool,
+    pub paused: bool,
+    pub started_at: DateTime<Utc>,
+    pub last_reward: Option<DateTime<Utc>>,
+}
+
+/// Bookkeeping for a spawned monitoring task
+struct Monitor {
+    started_at: DateTime<Utc>,
+    paused: Arc<AtomicBool>,
+    last_reward: Arc<RwLock<Option<DateTime<Utc>>>>,
+    stop: watch::Sender<bool>, // Checked between intervals, so a payout is never cut short
+    task: JoinHandle<()>,
+}
 
 /// Manages bandwidth sharing and rewards
 pub struct BandwidthManager {
     wallet_manager: Arc<WalletManager>,
+    meter: Arc<dyn BandwidthMeter>,
     metrics: Arc<RwLock<BandwidthMetrics>>,
-    reward_rate: f64, // Reward per MB of bandwidth
+    reward_rate: Arc<Mutex<f64>>, // Reward per MB of bandwidth, read by monitors every interval
+    cost_per_mb: f64, // Operator's cost per MB of bandwidth shared
     min_bandwidth: u64, // Minimum bandwidth requirement in bytes
+    max_bandwidth: Option<u64>, // Bytes per interval beyond which sharing earns nothing extra
     measurement_interval: Duration,
+    min_measurement_interval: Duration, // Floor for measurement_interval
+    warmup_intervals: u32, // Initial measurements that only establish a baseline
+    payout_threshold: f64, // Minimum pending reward before paying out
+    pending_rewards: Arc<RwLock<HashMap<Uuid, PendingReward>>>, // Keyed by monitored wallet
+    reward_ledger: Arc<RwLock<RewardLedger>>,
+    reward_currency: Option<CurrencyType>, // Pay rewards in this currency instead of the monitored wallet's
+    monitors: Arc<RwLock<HashMap<Uuid, Monitor>>>,
+    metrics_sender: broadcast::Sender<BandwidthMetrics>,
+    reward_sender: broadcast::Sender<RewardEvent>,
 }
 
 impl BandwidthManager {
     /// Create a new bandwidth manager
-    pub fn new(wallet_manager: Arc<WalletManager>) -> Self {
+    pub fn new(wallet_manager: Arc<WalletManager>, meter: Box<dyn BandwidthMeter>) -> Self {
         Self {
             wallet_manager,
-            metrics: Arc::new(RwLock::new(BandwidthMetrics {
-                total_bytes_shared: 0,
-                current_speed: 0,
-                uptime: Duration::from_secs(0),
-                last_reward: None,
-                start_time: Utc::now(),
-            })),
-            reward_rate: 0.0001, // Example: 0.0001 crypto per MB
+            meter: Arc::from(meter),
+            metrics: Arc::new(RwLock::new(BandwidthMetrics::new())),
+            reward_rate: Arc::new(Mutex::new(0.0001)), // Example: 0.0001 crypto per MB
+            cost_per_mb: 0.0,
             min_bandwidth: 1024 * 1024, // 1MB minimum
+            max_bandwidth: None,
             measurement_interval: Duration::from_secs(60),
+            min_measurement_interval: DEFAULT_MIN_MEASUREMENT_INTERVAL,
+            warmup_intervals: 1,
+            payout_threshold: 0.0, // Pay out every interval
+            pending_rewards: Arc::new(RwLock::new(HashMap::new())),
+            reward_ledger: Arc::new(RwLock::new(RewardLedger {
+                records: VecDeque::new(),
+                capacity: DEFAULT_REWARD_LEDGER_CAPACITY,
+            })),
+            reward_currency: None,
+            monitors: Arc::new(RwLock::new(HashMap::new())),
+            metrics_sender: broadcast::channel(100).0,
+            reward_sender: broadcast::channel(100).0,
         }
     }
 
     /// Start bandwidth monitoring and reward distribution
+    ///
+    /// Monitoring runs until `stop_monitoring` is called or the wallet is
+    /// deleted from the wallet manager.
     pub async fn start_monitoring(&self, wallet_id: Uuid) -> Result<()> {
+        let mut monitors = self.monitors.write().await;
+        if monitors.get(&wallet_id).is_some_and(|m| !m.task.is_finished()) {
+            return Err(CryptoNodeError::ResourceBusy(format!("Wallet {} is already being monitored", wallet_id)));
+        }
+
+        let paused = Arc::new(AtomicBool::new(false));
+        let last_reward = Arc::new(RwLock::new(None));
+        let task_paused = paused.clone();
+        let task_last_reward = last_reward.clone();
         let metrics = self.metrics.clone();
         let wallet_manager = self.wallet_manager.clone();
-        let reward_rate = self.reward_rate;
+        let meter = self.meter.clone();
+        let reward_rate = self.reward_rate.clone();
         let min_bandwidth = self.min_bandwidth;
+        let max_bandwidth = self.max_bandwidth;
         let interval_duration = self.measurement_interval;
+        let mut warmup_remaining = self.warmup_intervals;
+        let payout_threshold = self.payout_threshold;
+        let pending_rewards = self.pending_rewards.clone();
+        let reward_ledger = self.reward_ledger.clone();
+        let reward_currency = self.reward_currency.clone();
+        let metrics_sender = self.metrics_sender.clone();
+        let reward_sender = self.reward_sender.clone();
+        let (stop, mut stop_signal) = watch::channel(false);
+        let mut wallet_events = self.wallet_manager.subscribe();
 
-        tokio::spawn(async move {
+        let task = tasks::spawn(format!("bandwidth-monitor {}", wallet_id), async move {
             let mut interval = interval(interval_duration);
+            interval.tick().await;
+            let mut last_tick = Instant::now();
 
             loop {
-                interval.tick().await;
+                // A dropped sender also stops the monitor
+                let stopped = tokio::select! {
+                    _ = interval.tick() => false,
+                    _ = stop_signal.changed() => true,
+                    _ = wallet_deleted(&wallet_manager, &mut wallet_events, wallet_id) => {
+                        info!("Wallet {} was deleted, stopping its bandwidth monitor", wallet_id);
+                        true
+                    }
+                };
+                if stopped {
+                    break;
+                }
+
+                // Ticks can run late, so account for the time that actually passed
+                let now = Instant::now();
+                let elapsed = now.duration_since(last_tick);
+                last_tick = now;
+                if elapsed.is_zero() {
+                    continue;
+                }
+
+                let bytes_this_interval = match meter.measure().await {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        warn!("Bandwidth measurement failed: {}", e);
+                        continue;
+                    }
+                };
+
+                // Keep sampling while paused so the delta after resuming doesn't
+                // include the paused period
+                if task_paused.load(Ordering::SeqCst) {
+                    continue;
+                }
+
+                // The first deltas are taken against an uninitialized baseline
+                if warmup_remaining > 0 {
+                    warmup_remaining -= 1;
+                    continue;
+                }
 
                 // Update metrics
-                let mut current_metrics = metrics.write().await;
-                
-                // Simulate bandwidth measurement (replace with actual measurement)
-                let bytes_this_interval = measure_bandwidth().await;
-                current_metrics.total_bytes_shared += bytes_this_interval;
-                current_metrics.current_speed = bytes_this_interval as f64 / interval_duration.as_secs_f64();
-                current_metrics.uptime += interval_duration;
+                {
+                    let mut current_metrics = metrics.write().await;
+                    current_metrics.total_shared += bytes_this_interval;
+                    current_metrics.current_rate = bytes_this_interval as f64 / elapsed.as_secs_f64();
+                    current_metrics.smoothed_rate = if current_metrics.uptime.is_zero() {
+                        current_metrics.current_rate
+                    } else {
+                        smooth_rate(current_metrics.smoothed_rate, current_metrics.current_rate)
+                    };
+                    current_metrics.uptime += elapsed;
+                    current_metrics.last_updated = Utc::now();
+                }
 
                 // Check if minimum bandwidth requirement is met
-                if bytes_this_interval >= min_bandwidth {
-                    // Calculate reward
-                    let mb_shared = bytes_this_interval as f64 / (1024.0 * 1024.0);
+                let rewarded = rewarded_bytes(bytes_this_interval, elapsed, interval_duration, min_bandwidth, max_bandwidth);
+                if let Some(rewarded_bytes) = rewarded {
+                    let mb_shared = rewarded_bytes / (1024.0 * 1024.0);
+                    let reward_rate = *reward_rate.lock().unwrap_or_else(|e| e.into_inner());
                     let reward = mb_shared * reward_rate;
 
-                    // Update wallet balance
-                    if let Ok(wallet) = wallet_manager.get_wallet(wallet_id).await {
-                        let new_balance = wallet.balance + reward;
-                        let _ = wallet_manager.update_wallet_balance(wallet_id, new_balance).await;
-                        current_metrics.last_reward = Some(Utc::now());
+                    // Accumulate until the payout threshold is reached. Only this
+                    // task changes its entry, so `accrued` stays accurate while
+                    // the lock is released for the payout.
+                    let accrued = {
+                        let mut pending = pending_rewards.write().await;
+                        let entry = pending.entry(wallet_id).or_default();
+                        entry.amount += reward;
+                        entry.bytes += rewarded_bytes as u64;
+                        entry.amount
+                    };
+
+                    // Without a wallet in the reward currency the reward stays pending
+                    let payout_wallet = match &reward_currency {
+                        Some(currency) => find_reward_wallet(&wallet_manager, currency).await,
+                        None => Some(wallet_id),
+                    };
+
+                    if accrued >= payout_threshold {
+                        if let Some(payout_wallet) = payout_wallet {
+                            if let Ok(wallet) = wallet_manager.get_wallet(payout_wallet).await {
+                                // Round once, at payout; the remainder carries over to the next one
+                                let amount = payout_amount(&wallet.currency_type, accrued, wallet_manager.rounding_mode());
+                                if amount > 0.0 && wallet_manager.credit_wallet(payout_wallet, amount, BalanceChangeReason::Reward).await.is_ok() {
+                                    let now = Utc::now();
+                                    let bytes = {
+                                        let mut pending = pending_rewards.write().await;
+                                        let entry = pending.entry(wallet_id).or_default();
+                                        entry.amount -= amount;
+                                        std::mem::take(&mut entry.bytes)
+                                    };
+                                    let record = RewardRecord {
+                                        timestamp: now,
+                                        wallet_id: payout_wallet,
+                                        currency: wallet.currency_type.clone(),
+                                        bytes,
+                                        rate_used: reward_rate,
+                                        amount,
+                                    };
+                                    reward_ledger.write().await.record(record.clone());
+                                    {
+                                        let mut current_metrics = metrics.write().await;
+                                        *current_metrics.rewards.entry(wallet.currency_type.clone()).or_insert(0.0) += amount;
+                                        current_metrics.last_reward = Some(now);
+                                    }
+                                    *task_last_reward.write().await = Some(now);
+                                    let _ = reward_sender.send(RewardEvent::Paid(record));
+                                }
+                            }
+                        }
                     }
                 }
+
+                // Publish a snapshot once the interval is fully accounted for
+                let snapshot = metrics.read().await.clone();
+                let _ = metrics_sender.send(snapshot);
             }
         });
 
+        monitors.insert(wallet_id, Monitor {
+            started_at: Utc::now(),
+            paused,
+            last_reward,
+            stop,
+            task,
+        });
+
         Ok(())
     }
 
+    /// Stop monitoring a wallet and wait for its task to finish
+    ///
+    /// An interval that is already being processed completes first, so no
+    /// payout is left half-recorded.
+    pub async fn stop_monitoring(&self, wallet_id: Uuid) -> Result<()> {
+        let monitor = self.monitors.write().await.remove(&wallet_id)
+            .ok_or_else(|| CryptoNodeError::NotFound(format!("No monitor for wallet {}", wallet_id)))?;
+        let _ = monitor.stop.send(true);
+        let _ = monitor.task.await;
+        Ok(())
+    }
+
+    /// Pause monitoring for a wallet without stopping its task
+    pub async fn pause_monitoring(&self, wallet_id: Uuid) -> Result<()> {
+        self.set_paused(wallet_id, true).await
+    }
+
+    /// Resume a paused monitor
+    pub async fn resume_monitoring(&self, wallet_id: Uuid) -> Result<()> {
+        self.set_paused(wallet_id, false).await
+    }
+
+    async fn set_paused(&self, wallet_id: Uuid, paused: bool) -> Result<()> {
+        let monitors = self.monitors.read().await;
+        let monitor = monitors.get(&wallet_id)
+            .ok_or_else(|| CryptoNodeError::NotFound(format!("No monitor for wallet {}", wallet_id)))?;
+        monitor.paused.store(paused, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// List all monitors and their current state
+    pub async fn monitoring_status(&self) -> Vec<MonitorInfo> {
+        let monitors = self.monitors.read().await;
+        let mut status = Vec::with_capacity(monitors.len());
+        for (wallet_id, monitor) in monitors.iter() {
+            status.push(MonitorInfo {
+                wallet_id: *wallet_id,
+                running: !monitor.task.is_finished(),
+                paused: monitor.paused.load(Ordering::SeqCst),
+                started_at: monitor.started_at,
+                last_reward: *monitor.last_reward.read().await,
+            });
+        }
+        status
+    }
+
+    /// Subscribe to a metrics snapshot published after every measurement interval
+    pub fn subscribe_metrics(&self) -> broadcast::Receiver<BandwidthMetrics> {
+        self.metrics_sender.subscribe()
+    }
+
+    /// Subscribe to reward payouts
+    pub fn subscribe_rewards(&self) -> broadcast::Receiver<RewardEvent> {
+        self.reward_sender.subscribe()
+    }
+
     /// Get current bandwidth metrics
     pub async fn get_metrics(&self) -> Result<BandwidthMetrics> {
         let metrics = self.metrics.read().await;
@@ -86,11 +481,63 @@ impl BandwidthManager {
     }
 
     /// Update reward rate
-    pub async fn update_reward_rate(&mut self, new_rate: f64) -> Result<()> {
+    ///
+    /// Running monitors reward their next interval at the new rate; rewards
+    /// already accrued keep the rate they were earned at.
+    pub async fn update_reward_rate(&self, new_rate: f64) -> Result<()> {
         if new_rate < 0.0 {
             return Err(CryptoNodeError::InvalidInput("Reward rate cannot be negative".to_string()));
         }
-        self.reward_rate = new_rate;
+        *self.reward_rate.lock().unwrap_or_else(|e| e.into_inner()) = new_rate;
+        Ok(())
+    }
+
+    /// Current reward per MB
+    pub fn reward_rate(&self) -> f64 {
+        *self.reward_rate.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Update how often bandwidth is measured and rewarded
+    ///
+    /// Takes effect for monitors started after the change.
+    pub async fn update_measurement_interval(&mut self, new_interval: Duration) -> Result<()> {
+        if new_interval < self.min_measurement_interval {
+            return Err(CryptoNodeError::InvalidInput(format!(
+                "Measurement interval must be at least {:?}",
+                self.min_measurement_interval
+            )));
+        }
+        self.measurement_interval = new_interval;
+        Ok(())
+    }
+
+    /// Update the floor enforced on the measurement interval
+    pub async fn update_min_measurement_interval(&mut self, floor: Duration) -> Result<()> {
+        if floor.is_zero() {
+            return Err(CryptoNodeError::InvalidInput("Minimum measurement interval cannot be zero".to_string()));
+        }
+        if self.measurement_interval < floor {
+            return Err(CryptoNodeError::InvalidInput(format!(
+                "Current measurement interval {:?} is below the requested floor",
+                self.measurement_interval
+            )));
+        }
+        self.min_measurement_interval = floor;
+        Ok(())
+    }
+
+    /// Update how many initial intervals are used only to establish a baseline
+    pub async fn update_warmup_intervals(&mut self, intervals: u32) -> Result<()> {
+        self.warmup_intervals = intervals;
+        Ok(())
+    }
+
+    /// Update the cost of sharing one MB of bandwidth
+    pub async fn update_cost_per_mb(&mut self, new_cost: f64) -> Result<()> {
+        if new_cost < 0.0 {
+            return Err(CryptoNodeError::InvalidInput("Cost per MB cannot be negative".to_string()));
+        }
+        self.cost_per_mb = new_cost;
         Ok(())
     }
 
@@ -99,34 +546,648 @@ impl BandwidthManager {
         if new_min == 0 {
             return Err(CryptoNodeError::InvalidInput("Minimum bandwidth cannot be zero".to_string()));
         }
+        if self.max_bandwidth.is_some_and(|max| new_min > max) {
+            return Err(CryptoNodeError::InvalidInput("Minimum bandwidth cannot exceed the maximum".to_string()));
+        }
         self.min_bandwidth = new_min;
         Ok(())
     }
 
-    /// Calculate total rewards earned
-    pub async fn calculate_total_rewards(&self) -> Result<f64> {
-        let metrics = self.metrics.read().await;
-        let total_mb = metrics.total_bytes_shared as f64 / (1024.0 * 1024.0);
-        Ok(total_mb * self.reward_rate)
+    /// Update the per-interval bandwidth cap, or remove it with `None`
+    pub async fn update_max_bandwidth(&mut self, new_max: Option<u64>) -> Result<()> {
+        if new_max.is_some_and(|max| max < self.min_bandwidth) {
+            return Err(CryptoNodeError::InvalidInput("Maximum bandwidth cannot be below the minimum".to_string()));
+        }
+        self.max_bandwidth = new_max;
+        Ok(())
+    }
+
+    /// Realized reward per MB for a given throughput per measurement interval
+    ///
+    /// Zero below the minimum bandwidth; reduced above the cap, since bytes
+    /// beyond it are shared without reward.
+    pub fn effective_rate(&self, typical_bytes_per_interval: u64) -> Result<f64> {
+        if typical_bytes_per_interval == 0 || typical_bytes_per_interval < self.min_bandwidth {
+            return Ok(0.0);
+        }
+        let rewarded_bytes = match self.max_bandwidth {
+            Some(cap) => typical_bytes_per_interval.min(cap),
+            None => typical_bytes_per_interval,
+        };
+        Ok(self.reward_rate() * rewarded_bytes as f64 / typical_bytes_per_interval as f64)
+    }
+
+    /// Update the pending reward amount required before a payout
+    pub async fn update_payout_threshold(&mut self, new_threshold: f64) -> Result<()> {
+        if new_threshold < 0.0 {
+            return Err(CryptoNodeError::InvalidInput("Payout threshold cannot be negative".to_string()));
+        }
+        self.payout_threshold = new_threshold;
+        Ok(())
+    }
+
+    /// Set the currency rewards are paid in
+    ///
+    /// `None` pays rewards into the monitored wallet. Otherwise rewards go to
+    /// the oldest wallet of the given currency, or stay pending if none exists.
+    pub async fn update_reward_currency(&mut self, currency: Option<CurrencyType>) -> Result<()> {
+        self.reward_currency = currency;
+        Ok(())
+    }
+
+    /// Get rewards a wallet's monitor has accrued but not yet paid out
+    pub async fn get_pending_rewards(&self, wallet_id: Uuid) -> Result<f64> {
+        Ok(self.pending_rewards.read().await.get(&wallet_id).map_or(0.0, |pending| pending.amount))
+    }
+
+    /// Estimate how long until a wallet's pending rewards reach the payout threshold
+    ///
+    /// Projects from the smoothed rate, so one idle or bursty interval
+    /// doesn't swing the estimate, and counts no more than the bandwidth cap
+    /// would reward. Takes a `wallet_id` because each monitor accrues its
+    /// own pending rewards. Returns `None` when nothing is currently accruing.
+    pub async fn estimated_time_to_payout(&self, wallet_id: Uuid) -> Result<Option<Duration>> {
+        let smoothed_rate = self.metrics.read().await.smoothed_rate;
+        let interval_secs = self.measurement_interval.as_secs_f64();
+        if smoothed_rate <= 0.0 || smoothed_rate * interval_secs < self.min_bandwidth as f64 {
+            return Ok(None);
+        }
+        let rewarded_rate = match self.max_bandwidth {
+            Some(cap) => smoothed_rate.min(cap as f64 / interval_secs),
+            None => smoothed_rate,
+        };
+
+        let reward_per_sec = rewarded_rate / (1024.0 * 1024.0) * self.reward_rate();
+        if reward_per_sec <= 0.0 {
+            return Ok(None);
+        }
+
+        let pending = self.get_pending_rewards(wallet_id).await?;
+        let remaining = (self.payout_threshold - pending).max(0.0);
+        Ok(Some(Duration::from_secs_f64(remaining / reward_per_sec)))
+    }
+
+    /// Calculate total rewards credited to wallets, per currency
+    ///
+    /// Each payout is counted at the rate in effect when it was earned, so
+    /// later rate changes don't rewrite history. Rewards still below the
+    /// payout threshold are reported by `get_pending_rewards`.
+    pub async fn calculate_total_rewards(&self) -> Result<HashMap<CurrencyType, f64>> {
+        Ok(self.metrics.read().await.rewards.clone())
+    }
+
+    /// Reward payouts at or after `since`, oldest first
+    pub async fn reward_history(&self, since: DateTime<Utc>) -> Vec<RewardRecord> {
+        self.reward_ledger.read().await.records.iter()
+            .filter(|r| r.timestamp >= since)
+            .cloned()
+            .collect()
+    }
+
+    /// Limit how many payouts the reward ledger keeps, dropping the oldest
+    pub async fn update_reward_ledger_capacity(&self, capacity: usize) -> Result<()> {
+        if capacity == 0 {
+            return Err(CryptoNodeError::InvalidInput("Reward ledger capacity cannot be zero".to_string()));
+        }
+        let mut ledger = self.reward_ledger.write().await;
+        ledger.capacity = capacity;
+        while ledger.records.len() > capacity {
+            ledger.records.pop_front();
+        }
+        Ok(())
+    }
+
+    /// Drop ledger records older than `before`
+    pub async fn prune_reward_history(&self, before: DateTime<Utc>) {
+        self.reward_ledger.write().await.records.retain(|r| r.timestamp >= before);
     }
 
     /// Get estimated rewards per hour at current rate
     pub async fn get_estimated_hourly_rewards(&self) -> Result<f64> {
         let metrics = self.metrics.read().await;
-        let bytes_per_hour = metrics.current_speed * 3600.0;
+        let bytes_per_hour = metrics.current_rate * 3600.0;
         let mb_per_hour = bytes_per_hour / (1024.0 * 1024.0);
-        Ok(mb_per_hour * self.reward_rate)
+        Ok(mb_per_hour * self.reward_rate())
+    }
+
+    /// Get estimated hourly rewards minus the cost of the bandwidth shared
+    ///
+    /// A negative result means sharing is currently unprofitable.
+    pub async fn net_estimated_hourly_rewards(&self) -> Result<f64> {
+        let metrics = self.metrics.read().await;
+        let mb_per_hour = metrics.current_rate * 3600.0 / (1024.0 * 1024.0);
+        Ok(mb_per_hour * (self.reward_rate() - self.cost_per_mb))
+    }
+}
+
+/// Wait until `wallet_id` is deleted
+///
+/// If events were dropped the wallet is looked up, since the deletion may
+/// have been among them. Never completes if the wallet manager stops
+/// sending events.
+async fn wallet_deleted(wallet_manager: &WalletManager, events: &mut broadcast::Receiver<WalletEvent>, wallet_id: Uuid) {
+    loop {
+        match events.recv().await {
+            Ok(WalletEvent::WalletDeleted { wallet_id: deleted }) if deleted == wallet_id => return,
+            Ok(_) => {}
+            Err(broadcast::error::RecvError::Lagged(_)) => {
+                if matches!(wallet_manager.get_wallet(wallet_id).await, Err(CryptoNodeError::NotFound(_))) {
+                    return;
+                }
+            }
+            Err(broadcast::error::RecvError::Closed) => std::future::pending().await,
+        }
+    }
+}
+
+/// Bytes that earn a reward over `elapsed`, or `None` below the minimum
+///
+/// The minimum and the cap are per `interval` and scale with the time that
+/// actually passed, so late and early ticks are rewarded proportionally.
+fn rewarded_bytes(bytes: u64, elapsed: Duration, interval: Duration, min_bandwidth: u64, max_bandwidth: Option<u64>) -> Option<f64> {
+    let scale = elapsed.as_secs_f64() / interval.as_secs_f64();
+    if (bytes as f64) < min_bandwidth as f64 * scale {
+        return None;
+    }
+    // Bytes above the cap are shared without reward
+    Some(match max_bandwidth {
+        Some(cap) => (bytes as f64).min(cap as f64 * scale),
+        None => bytes as f64,
+    })
+}
+
+/// Fold an interval's rate into an exponentially weighted moving average
+fn smooth_rate(previous: f64, sample: f64) -> f64 {
+    RATE_SMOOTHING * sample + (1.0 - RATE_SMOOTHING) * previous
+}
+
+/// Round `accrued` for payout, never paying more than was accrued
+///
+/// Modes that can round up fall back to rounding down when they would,
+/// so the pending remainder never goes negative.
+fn payout_amount(currency: &CurrencyType, accrued: f64, rounding: RoundingMode) -> f64 {
+    let amount = currency.round(accrued, rounding);
+    if amount > accrued {
+        currency.round(accrued, RoundingMode::Down)
+    } else {
+        amount
     }
 }
 
-/// Simulated bandwidth measurement function
-/// Replace this with actual bandwidth measurement implementation
-async fn measure_bandwidth() -> u64 {
-    // This is a placeholder that simulates bandwidth measurement
-    // In a real implementation, this would measure actual network usage
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
-    
-    // Simulate bandwidth between 1MB and 10MB per interval
-    rng.gen_range(1_048_576..10_485_760)
-} 
\ No newline at end of file
+/// Find the oldest wallet holding the given reward currency
+async fn find_reward_wallet(wallet_manager: &WalletManager, currency: &CurrencyType) -> Option<Uuid> {
+    let wallets = wallet_manager.list_wallets().await.ok()?;
+    wallets.into_iter()
+        .filter(|w| &w.currency_type == currency)
+        .min_by_key(|w| w.created_at)
+        .map(|w| w.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> BandwidthManager {
+        BandwidthManager::new(Arc::new(WalletManager::new()), Box::new(MockMeter::new()))
+    }
+
+    #[tokio::test]
+    async fn measurement_interval_below_floor_is_rejected() {
+        let mut manager = manager();
+        let below = DEFAULT_MIN_MEASUREMENT_INTERVAL - Duration::from_secs(1);
+        assert!(matches!(manager.update_measurement_interval(below).await, Err(CryptoNodeError::InvalidInput(_))));
+        assert!(manager.update_measurement_interval(DEFAULT_MIN_MEASUREMENT_INTERVAL).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn floor_cannot_be_raised_above_current_interval() {
+        let mut manager = manager();
+        assert!(manager.update_min_measurement_interval(Duration::from_secs(120)).await.is_err());
+        manager.update_min_measurement_interval(Duration::from_secs(30)).await.unwrap();
+        assert!(manager.update_measurement_interval(Duration::from_secs(20)).await.is_err());
+    }
+
+    #[test]
+    fn payout_never_exceeds_accrued() {
+        // Bitcoin has 8 decimals, so this rounds up under both half modes
+        let accrued = 0.123456786;
+        for mode in [RoundingMode::HalfUp, RoundingMode::HalfEven, RoundingMode::Down] {
+            let amount = payout_amount(&CurrencyType::Bitcoin, accrued, mode);
+            assert!(amount <= accrued, "{:?} paid {}", mode, amount);
+            assert_eq!(amount, 0.12345678);
+        }
+        assert_eq!(payout_amount(&CurrencyType::Bitcoin, 0.123456784, RoundingMode::HalfUp), 0.12345678);
+    }
+
+    #[tokio::test]
+    async fn deletion_lost_to_lag_is_still_noticed() {
+        let wallet_manager = WalletManager::new();
+        let monitored = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let other = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let mut events = wallet_manager.subscribe();
+
+        // Push the deletion out of the receiver's buffer
+        wallet_manager.delete_wallet(monitored.id).await.unwrap();
+        for balance in 1..=200 {
+            wallet_manager.update_wallet_balance(other.id, balance as f64).await.unwrap();
+        }
+
+        let noticed = tokio::time::timeout(
+            Duration::from_secs(1),
+            wallet_deleted(&wallet_manager, &mut events, monitored.id),
+        ).await;
+        assert!(noticed.is_ok());
+    }
+
+    /// Meter returning a fixed sequence of readings, then nothing
+    struct FakeMeter {
+        readings: Mutex<VecDeque<u64>>,
+    }
+
+    impl BandwidthMeter for FakeMeter {
+        fn measure(&self) -> BoxFuture<'_, Result<u64>> {
+            Box::pin(async move {
+                Ok(self.readings.lock().unwrap().pop_front().unwrap_or(0))
+            })
+        }
+    }
+
+    const MIB: u64 = 1024 * 1024;
+
+    const INTERVAL: Duration = Duration::from_millis(10);
+
+    /// A manager measuring `readings` every `INTERVAL` and rewarding 0.5 per MiB
+    ///
+    /// The first reading only establishes the baseline.
+    async fn fast_manager(wallet_manager: Arc<WalletManager>, readings: Vec<u64>) -> BandwidthManager {
+        let meter = FakeMeter { readings: Mutex::new(readings.into()) };
+        let mut manager = BandwidthManager::new(wallet_manager, Box::new(meter));
+        manager.update_min_measurement_interval(INTERVAL).await.unwrap();
+        manager.update_measurement_interval(INTERVAL).await.unwrap();
+        manager.update_min_bandwidth(1).await.unwrap();
+        manager.update_reward_rate(0.5).await.unwrap();
+        manager
+    }
+
+    /// Let spawned monitors process everything that is due on the paused clock
+    async fn settle() {
+        for _ in 0..16 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    /// Advance the paused clock by `by` and let the monitors catch up
+    async fn advance(by: Duration) {
+        settle().await;
+        tokio::time::advance(by).await;
+        settle().await;
+    }
+
+    /// Let `n` measurement intervals pass on the paused clock
+    async fn run_intervals(n: u32) {
+        for _ in 0..n {
+            advance(INTERVAL).await;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rewards_accrue_from_metered_bytes() {
+        let wallet_manager = Arc::new(WalletManager::new());
+        let wallet = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let mut manager = fast_manager(wallet_manager, vec![7 * MIB, MIB, 2 * MIB, 3 * MIB]).await;
+        manager.update_payout_threshold(1_000.0).await.unwrap();
+
+        manager.start_monitoring(wallet.id).await.unwrap();
+        run_intervals(4).await;
+        manager.stop_monitoring(wallet.id).await.unwrap();
+
+        assert_eq!(manager.get_metrics().await.unwrap().total_shared, 6 * MIB);
+        assert_eq!(manager.get_pending_rewards(wallet.id).await.unwrap(), 3.0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn interface_totals_skip_loopback() {
+        let contents = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 5000      10    0    0    0     0          0         0     5000      10    0    0    0     0       0          0
+  eth0: 1200      12    0    0    0     0          0         0      800       8    0    0    0     0       0          0
+ wlan0:  100       1    0    0    0     0          0         0       50       1    0    0    0     0       0          0
+";
+        assert_eq!(parse_proc_net_dev(contents), 2150);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn monitors_pay_their_own_wallets() {
+        let wallet_manager = Arc::new(WalletManager::new());
+        let bitcoin = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let ethereum = wallet_manager.create_wallet(CurrencyType::Ethereum).await.unwrap();
+        let manager = fast_manager(wallet_manager.clone(), vec![MIB; 40]).await;
+
+        manager.start_monitoring(bitcoin.id).await.unwrap();
+        manager.start_monitoring(ethereum.id).await.unwrap();
+        // Both monitors read the same meter, one reading each per interval
+        run_intervals(16).await;
+        assert_eq!(manager.get_metrics().await.unwrap().total_shared, 30 * MIB);
+        manager.stop_monitoring(bitcoin.id).await.unwrap();
+        manager.stop_monitoring(ethereum.id).await.unwrap();
+
+        let history = manager.reward_history(DateTime::<Utc>::MIN_UTC).await;
+        for wallet in [bitcoin, ethereum] {
+            let paid: Vec<_> = history.iter().filter(|r| r.wallet_id == wallet.id).collect();
+            assert!(!paid.is_empty());
+            assert!(paid.iter().all(|r| r.currency == wallet.currency_type));
+            let total: f64 = paid.iter().map(|r| r.amount).sum();
+            let balance = wallet_manager.get_wallet(wallet.id).await.unwrap().balance;
+            assert!((balance - total).abs() < 1e-9);
+        }
+    }
+
+    #[tokio::test]
+    async fn time_to_payout_uses_each_wallets_pending_reward() {
+        let mut manager = manager();
+        manager.update_reward_rate(0.5).await.unwrap();
+        manager.update_payout_threshold(10.0).await.unwrap();
+        let (accruing, idle) = (Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(manager.estimated_time_to_payout(accruing).await.unwrap(), None);
+
+        manager.metrics.write().await.smoothed_rate = MIB as f64;
+        manager.pending_rewards.write().await.insert(accruing, PendingReward { amount: 4.0, bytes: 8 * MIB });
+
+        // 0.5 per second at 1 MiB/s
+        assert_eq!(manager.estimated_time_to_payout(accruing).await.unwrap(), Some(Duration::from_secs(12)));
+        assert_eq!(manager.estimated_time_to_payout(idle).await.unwrap(), Some(Duration::from_secs(20)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rewards_go_to_the_reward_currency_wallet() {
+        let wallet_manager = Arc::new(WalletManager::new());
+        let monitored = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let payout = wallet_manager.create_wallet(CurrencyType::Ethereum).await.unwrap();
+        let mut manager = fast_manager(wallet_manager.clone(), vec![MIB, 2 * MIB]).await;
+        manager.update_reward_currency(Some(CurrencyType::Ethereum)).await.unwrap();
+
+        manager.start_monitoring(monitored.id).await.unwrap();
+        run_intervals(2).await;
+        manager.stop_monitoring(monitored.id).await.unwrap();
+
+        assert_eq!(wallet_manager.get_wallet(payout.id).await.unwrap().balance, 1.0);
+        assert_eq!(wallet_manager.get_wallet(monitored.id).await.unwrap().balance, 0.0);
+        let history = manager.reward_history(DateTime::<Utc>::MIN_UTC).await;
+        assert!(history.iter().all(|r| r.wallet_id == payout.id && r.currency == CurrencyType::Ethereum));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rewards_stay_pending_without_a_reward_currency_wallet() {
+        let wallet_manager = Arc::new(WalletManager::new());
+        let monitored = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let mut manager = fast_manager(wallet_manager.clone(), vec![MIB, 2 * MIB]).await;
+        manager.update_reward_currency(Some(CurrencyType::Ethereum)).await.unwrap();
+
+        manager.start_monitoring(monitored.id).await.unwrap();
+        run_intervals(2).await;
+        manager.stop_monitoring(monitored.id).await.unwrap();
+
+        assert_eq!(manager.get_pending_rewards(monitored.id).await.unwrap(), 1.0);
+        assert_eq!(wallet_manager.get_wallet(monitored.id).await.unwrap().balance, 0.0);
+        assert!(manager.reward_history(DateTime::<Utc>::MIN_UTC).await.is_empty());
+    }
+
+    #[test]
+    fn minimum_and_cap_scale_with_elapsed_time() {
+        let interval = Duration::from_secs(60);
+        let (min, cap) = (10 * MIB, Some(100 * MIB));
+
+        // A full interval
+        assert_eq!(rewarded_bytes(50 * MIB, interval, interval, min, cap), Some((50 * MIB) as f64));
+        assert_eq!(rewarded_bytes(150 * MIB, interval, interval, min, cap), Some((100 * MIB) as f64));
+
+        // A tick after half an interval needs, and may earn, only half as much
+        let half = Duration::from_secs(30);
+        assert_eq!(rewarded_bytes(6 * MIB, half, interval, min, cap), Some((6 * MIB) as f64));
+        assert_eq!(rewarded_bytes(80 * MIB, half, interval, min, cap), Some((50 * MIB) as f64));
+        assert_eq!(rewarded_bytes(4 * MIB, half, interval, min, cap), None);
+
+        // A tick delayed to two intervals is measured against twice the minimum and cap
+        let double = Duration::from_secs(120);
+        assert_eq!(rewarded_bytes(15 * MIB, double, interval, min, cap), None);
+        assert_eq!(rewarded_bytes(250 * MIB, double, interval, min, cap), Some((200 * MIB) as f64));
+    }
+
+    #[tokio::test]
+    async fn monitoring_status_tracks_start_pause_and_stop() {
+        let wallet_manager = Arc::new(WalletManager::new());
+        let wallet = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let manager = BandwidthManager::new(wallet_manager, Box::new(MockMeter::new()));
+        assert!(manager.monitoring_status().await.is_empty());
+
+        manager.start_monitoring(wallet.id).await.unwrap();
+        let status = manager.monitoring_status().await;
+        assert_eq!(status.len(), 1);
+        assert_eq!(status[0].wallet_id, wallet.id);
+        assert!(status[0].running && !status[0].paused);
+        assert_eq!(status[0].last_reward, None);
+
+        manager.pause_monitoring(wallet.id).await.unwrap();
+        assert!(manager.monitoring_status().await[0].paused);
+        manager.resume_monitoring(wallet.id).await.unwrap();
+        assert!(!manager.monitoring_status().await[0].paused);
+
+        manager.stop_monitoring(wallet.id).await.unwrap();
+        assert!(manager.monitoring_status().await.is_empty());
+        assert!(matches!(manager.pause_monitoring(wallet.id).await, Err(CryptoNodeError::NotFound(_))));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bytes_measured_while_paused_earn_nothing() {
+        let wallet_manager = Arc::new(WalletManager::new());
+        let wallet = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let mut manager = fast_manager(wallet_manager, vec![MIB; 30]).await;
+        manager.update_payout_threshold(1_000.0).await.unwrap();
+
+        manager.start_monitoring(wallet.id).await.unwrap();
+        manager.pause_monitoring(wallet.id).await.unwrap();
+        run_intervals(10).await;
+        assert_eq!(manager.get_metrics().await.unwrap().total_shared, 0);
+        assert_eq!(manager.get_pending_rewards(wallet.id).await.unwrap(), 0.0);
+
+        // Only readings taken after resuming count, and the first is still the baseline
+        manager.resume_monitoring(wallet.id).await.unwrap();
+        run_intervals(2).await;
+        manager.stop_monitoring(wallet.id).await.unwrap();
+        assert_eq!(manager.get_metrics().await.unwrap().total_shared, MIB);
+        assert_eq!(manager.get_pending_rewards(wallet.id).await.unwrap(), 0.5);
+    }
+
+    /// A manager sharing 1 MiB per second at 0.5 per MB
+    async fn sharing_one_mib_per_second() -> BandwidthManager {
+        let manager = manager();
+        manager.update_reward_rate(0.5).await.unwrap();
+        manager.metrics.write().await.current_rate = MIB as f64;
+        manager
+    }
+
+    #[tokio::test]
+    async fn net_hourly_rewards_subtract_bandwidth_cost() {
+        let mut manager = sharing_one_mib_per_second().await;
+        manager.update_cost_per_mb(0.125).await.unwrap();
+
+        assert_eq!(manager.get_estimated_hourly_rewards().await.unwrap(), 1800.0);
+        assert_eq!(manager.net_estimated_hourly_rewards().await.unwrap(), 1350.0);
+    }
+
+    #[tokio::test]
+    async fn net_hourly_rewards_go_negative_when_unprofitable() {
+        let mut manager = sharing_one_mib_per_second().await;
+        manager.update_cost_per_mb(0.75).await.unwrap();
+
+        assert_eq!(manager.net_estimated_hourly_rewards().await.unwrap(), -900.0);
+        assert!(manager.update_cost_per_mb(-1.0).await.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn warm_up_readings_earn_nothing() {
+        let wallet_manager = Arc::new(WalletManager::new());
+        let wallet = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        // Uninitialized baselines read as huge deltas
+        let mut manager = fast_manager(wallet_manager, vec![500 * MIB, 500 * MIB, MIB, MIB]).await;
+        manager.update_warmup_intervals(2).await.unwrap();
+        manager.update_payout_threshold(1_000.0).await.unwrap();
+
+        manager.start_monitoring(wallet.id).await.unwrap();
+        run_intervals(6).await;
+        manager.stop_monitoring(wallet.id).await.unwrap();
+
+        assert_eq!(manager.get_metrics().await.unwrap().total_shared, 2 * MIB);
+        assert_eq!(manager.get_pending_rewards(wallet.id).await.unwrap(), 1.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn metrics_snapshot_is_published_every_interval() {
+        let wallet_manager = Arc::new(WalletManager::new());
+        let wallet = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let manager = fast_manager(wallet_manager, vec![0, MIB, 2 * MIB, 3 * MIB]).await;
+        let mut snapshots = manager.subscribe_metrics();
+
+        manager.start_monitoring(wallet.id).await.unwrap();
+        run_intervals(1).await; // The baseline reading publishes nothing
+        assert!(snapshots.try_recv().is_err());
+
+        for expected in [MIB, 3 * MIB, 6 * MIB] {
+            run_intervals(1).await;
+            assert_eq!(snapshots.try_recv().unwrap().total_shared, expected);
+            assert!(snapshots.try_recv().is_err());
+        }
+        manager.stop_monitoring(wallet.id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn effective_rate_applies_minimum_and_cap() {
+        let mut manager = manager();
+        manager.update_reward_rate(0.5).await.unwrap();
+        manager.update_min_bandwidth(MIB).await.unwrap();
+        manager.update_max_bandwidth(Some(4 * MIB)).await.unwrap();
+
+        assert_eq!(manager.effective_rate(MIB - 1).unwrap(), 0.0);
+        assert_eq!(manager.effective_rate(2 * MIB).unwrap(), 0.5);
+        assert_eq!(manager.effective_rate(8 * MIB).unwrap(), 0.25);
+        assert!(manager.update_max_bandwidth(Some(MIB - 1)).await.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ledger_explains_every_payout() {
+        let wallet_manager = Arc::new(WalletManager::new());
+        let wallet = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let manager = fast_manager(wallet_manager.clone(), vec![0, MIB, 2 * MIB, 3 * MIB]).await;
+
+        manager.start_monitoring(wallet.id).await.unwrap();
+        run_intervals(4).await;
+        manager.stop_monitoring(wallet.id).await.unwrap();
+
+        let history = manager.reward_history(DateTime::<Utc>::MIN_UTC).await;
+        assert_eq!(history.iter().map(|r| r.bytes).collect::<Vec<_>>(), vec![MIB, 2 * MIB, 3 * MIB]);
+        assert!(history.iter().all(|r| r.rate_used == 0.5 && r.amount == r.bytes as f64 / MIB as f64 * 0.5));
+        let paid: f64 = history.iter().map(|r| r.amount).sum();
+        assert_eq!(wallet_manager.get_wallet(wallet.id).await.unwrap().balance, paid);
+
+        manager.update_reward_ledger_capacity(1).await.unwrap();
+        let history = manager.reward_history(DateTime::<Utc>::MIN_UTC).await;
+        assert_eq!(history.iter().map(|r| r.bytes).collect::<Vec<_>>(), vec![3 * MIB]);
+        assert!(manager.reward_history(Utc::now()).await.is_empty());
+        assert!(manager.update_reward_ledger_capacity(0).await.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_changes_apply_to_running_monitors_from_the_next_interval() {
+        let wallet_manager = Arc::new(WalletManager::new());
+        let wallet = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let manager = fast_manager(wallet_manager.clone(), vec![0, 2 * MIB, 2 * MIB, 2 * MIB]).await;
+
+        manager.start_monitoring(wallet.id).await.unwrap();
+        run_intervals(2).await;
+        manager.update_reward_rate(0.25).await.unwrap();
+        run_intervals(2).await;
+        manager.stop_monitoring(wallet.id).await.unwrap();
+
+        // One interval at 0.5 per MiB, then two at 0.25, none of them repriced
+        let history = manager.reward_history(DateTime::<Utc>::MIN_UTC).await;
+        assert_eq!(history.iter().map(|r| (r.rate_used, r.amount)).collect::<Vec<_>>(), vec![(0.5, 1.0), (0.25, 0.5), (0.25, 0.5)]);
+        assert_eq!(manager.calculate_total_rewards().await.unwrap()[&CurrencyType::Bitcoin], 2.0);
+        assert_eq!(wallet_manager.get_wallet(wallet.id).await.unwrap().balance, 2.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn late_and_early_ticks_are_rewarded_for_the_time_that_passed() {
+        let wallet_manager = Arc::new(WalletManager::new());
+        let wallet = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let mut manager = fast_manager(wallet_manager, vec![0, 4 * MIB, 4 * MIB]).await;
+        manager.update_max_bandwidth(Some(MIB)).await.unwrap(); // Per interval, so the cap binds
+
+        manager.start_monitoring(wallet.id).await.unwrap();
+        run_intervals(1).await;
+        // The next tick runs half an interval late, and the one after is due half an interval later
+        advance(INTERVAL * 3 / 2).await;
+        advance(INTERVAL / 2).await;
+        manager.stop_monitoring(wallet.id).await.unwrap();
+
+        let history = manager.reward_history(DateTime::<Utc>::MIN_UTC).await;
+        assert_eq!(history.iter().map(|r| r.bytes).collect::<Vec<_>>(), vec![3 * MIB / 2, MIB / 2]);
+        assert_eq!(history.iter().map(|r| r.amount).collect::<Vec<_>>(), vec![0.75, 0.25]);
+    }
+
+    #[tokio::test]
+    async fn time_to_payout_counts_only_capped_bandwidth() {
+        let mut manager = manager();
+        manager.update_reward_rate(0.5).await.unwrap();
+        manager.update_payout_threshold(10.0).await.unwrap();
+        manager.update_max_bandwidth(Some(30 * MIB)).await.unwrap(); // Half a MiB per second
+        manager.metrics.write().await.smoothed_rate = MIB as f64;
+
+        assert_eq!(manager.estimated_time_to_payout(Uuid::new_v4()).await.unwrap(), Some(Duration::from_secs(40)));
+    }
+
+    #[test]
+    fn one_idle_interval_only_dents_the_smoothed_rate() {
+        let steady = (0..20).fold(MIB as f64, |rate, _| smooth_rate(rate, MIB as f64));
+        assert_eq!(steady, MIB as f64);
+        let after_idle = smooth_rate(steady, 0.0);
+        assert!((after_idle - 0.7 * MIB as f64).abs() < 1e-6);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn monitors_keep_a_smoothed_rate() {
+        let wallet_manager = Arc::new(WalletManager::new());
+        let wallet = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let manager = fast_manager(wallet_manager, vec![0, 10 * MIB, 0]).await;
+
+        manager.start_monitoring(wallet.id).await.unwrap();
+        run_intervals(3).await;
+        manager.stop_monitoring(wallet.id).await.unwrap();
+
+        // 10 MiB per interval is 1000 MiB/s; the idle interval that follows weighs 30%
+        let metrics = manager.get_metrics().await.unwrap();
+        assert_eq!(metrics.current_rate, 0.0);
+        assert!((metrics.smoothed_rate - 700.0 * MIB as f64).abs() < 1.0);
+    }
+}
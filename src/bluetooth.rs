@@ -1,13 +1,29 @@
-use crate::{Result, error::CryptoNodeError};
+use crate::{
+    Result,
+    error::CryptoNodeError,
+    retry::{RetryPolicy, retry_with_policy},
+    tasks,
+    types::ConnectionStatus,
+};
 use btleplug::api::{
-    Central, CentralEvent, Characteristic, Manager as _, Peripheral as _, ScanFilter, WriteType
+    Central, CentralEvent, CharPropFlags, Characteristic, Manager as _, Peripheral as _, PeripheralProperties,
+    ScanFilter, WriteType
 };
 use btleplug::platform::{Adapter, Manager, Peripheral};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
 use futures::stream::StreamExt;
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
 use uuid::Uuid;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
+use tracing::{info, warn};
 
 /// Service UUID for our custom BLE service
 pub const SERVICE_UUID: Uuid = Uuid::from_u128(0x12345678_1234_1234_1234_123456789ABC);
@@ -19,12 +35,390 @@ pub const CHARACTERISTIC_UUIDS: &[Uuid] = &[
     Uuid::from_u128(0x12345678_1234_1234_1234_123456789ABE), // Notification
 ];
 
+/// Role of each entry in `CHARACTERISTIC_UUIDS`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CharacteristicRole {
+    Command,
+    Response,
+    Notification,
+}
+
+impl CharacteristicRole {
+    /// UUID of the characteristic filling this role
+    pub fn uuid(&self) -> Uuid {
+        match self {
+            CharacteristicRole::Command => CHARACTERISTIC_UUIDS[0],
+            CharacteristicRole::Response => CHARACTERISTIC_UUIDS[1],
+            CharacteristicRole::Notification => CHARACTERISTIC_UUIDS[2],
+        }
+    }
+}
+
+/// Status snapshot pushed to a connected controller app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryFrame {
+    pub timestamp: DateTime<Utc>,
+    pub battery_level: Option<f32>,
+    pub current_bandwidth: f64,
+    pub balances: HashMap<Uuid, f64>,
+}
+
+/// Request written by a connected controller to the command characteristic
+///
+/// Encoded as JSON tagged by `command`, e.g.
+/// `{"command":"GetBalance","wallet_id":"..."}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command")]
+pub enum DeviceCommand {
+    /// Reply with the wallet's `BalanceView`
+    GetBalance { wallet_id: Uuid },
+}
+
+impl DeviceCommand {
+    /// Decode a command from received bytes
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        serde_json::from_slice(data)
+            .map_err(|e| CryptoNodeError::InvalidInput(format!("Invalid command: {}", e)))
+    }
+}
+
+/// What `BluetoothManager::receive_command` does with bytes that aren't a known command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownCommandPolicy {
+    /// Drop the frame silently
+    Ignore,
+    /// Emit `BluetoothEvent::Error`
+    #[default]
+    Error,
+    /// Write a `CommandErrorResponse` back to the controller
+    RespondWithError,
+}
+
+/// Response written to the controller for a command that failed to decode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandErrorResponse {
+    pub error: String,
+}
+
+/// How an undecodable command is dealt with under an `UnknownCommandPolicy`
+#[derive(Debug, Clone)]
+pub enum UnknownCommandAction {
+    Drop,
+    Emit(BluetoothEvent),
+    Respond(Vec<u8>),
+}
+
+impl UnknownCommandPolicy {
+    /// Decide what to do about a command that failed to decode with `err`
+    pub fn action(&self, err: &CryptoNodeError) -> Result<UnknownCommandAction> {
+        Ok(match self {
+            UnknownCommandPolicy::Ignore => UnknownCommandAction::Drop,
+            UnknownCommandPolicy::Error => UnknownCommandAction::Emit(BluetoothEvent::Error(err.to_string())),
+            UnknownCommandPolicy::RespondWithError => {
+                let response = CommandErrorResponse { error: err.to_string() };
+                UnknownCommandAction::Respond(serde_json::to_vec(&response)
+                    .map_err(|e| CryptoNodeError::Serialization(e.to_string()))?)
+            }
+        })
+    }
+}
+
+/// Supplies telemetry frames for `BluetoothManager::start_telemetry`
+pub trait TelemetryProvider: Send + Sync {
+    fn telemetry(&self) -> Result<TelemetryFrame>;
+}
+
+/// Handle to a running telemetry task
+pub struct TelemetryHandle {
+    task: JoinHandle<()>,
+}
+
+impl TelemetryHandle {
+    /// Stop sending telemetry
+    pub fn stop(self) {
+        self.task.abort();
+    }
+
+    /// Whether the telemetry task is still running
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+/// Default number of discovered devices kept in the cache
+pub const DEFAULT_MAX_DISCOVERED: usize = 256;
+
+/// A device seen while scanning
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredDevice {
+    pub id: String,
+    pub name: Option<String>,
+    pub rssi: Option<i16>,
+    pub last_seen: DateTime<Utc>,
+    pub stale: bool, // Not seen since the last scan stopped
+}
+
+/// What `stop_scan` does with the discovered-devices cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiscoveryPruning {
+    /// Keep every entry, marked stale
+    #[default]
+    MarkStale,
+    /// Empty the cache
+    Clear,
+    /// Drop entries not seen for this long and mark the rest stale
+    AgeOut(Duration),
+}
+
+/// What `connect_to_device` does when another device is already connected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConnectPolicy {
+    /// Fail with `ResourceBusy`
+    RejectIfConnected,
+    /// Disconnect the current device first
+    #[default]
+    DisconnectExisting,
+}
+
+impl ConnectPolicy {
+    /// Whether the existing connection must be dropped before connecting to `new`
+    ///
+    /// Reconnecting to the device that is already connected never conflicts.
+    fn must_disconnect<I: PartialEq>(&self, existing: Option<&I>, new: &I) -> Result<bool> {
+        if existing.is_none_or(|id| id == new) {
+            return Ok(false);
+        }
+        match self {
+            ConnectPolicy::RejectIfConnected => {
+                Err(CryptoNodeError::ResourceBusy("Another device is already connected".to_string()))
+            }
+            ConnectPolicy::DisconnectExisting => Ok(true),
+        }
+    }
+}
+
+/// Counts consecutive operation errors towards an adapter reset
+#[derive(Default)]
+struct ErrorStreak {
+    threshold: Option<u32>, // `None` never resets
+    consecutive: AtomicU32,
+    resetting: AtomicBool,
+}
+
+impl ErrorStreak {
+    /// Record an operation result, returning the streak length if a reset should start
+    fn record(&self, failed: bool) -> Option<u32> {
+        if !failed {
+            self.consecutive.store(0, Ordering::SeqCst);
+            return None;
+        }
+
+        let errors = self.consecutive.fetch_add(1, Ordering::SeqCst) + 1;
+        if self.threshold.is_none_or(|threshold| errors < threshold) {
+            return None;
+        }
+        // Errors from operations racing the reset must not trigger another one
+        self.resetting.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).ok()?;
+        Some(errors)
+    }
+
+    /// Start counting afresh once a reset has finished
+    fn finish_reset(&self) {
+        self.consecutive.store(0, Ordering::SeqCst);
+        self.resetting.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Stops reconnecting after repeated failed connect cycles
+struct ReconnectBreaker {
+    max_failures: usize,
+    window: Duration,
+    failures: VecDeque<Instant>,
+    open: bool,
+}
+
+impl ReconnectBreaker {
+    /// Record a failed connect cycle, returning true if this opened the circuit
+    fn record_failure(&mut self) -> bool {
+        let now = Instant::now();
+        self.failures.push_back(now);
+        while self.failures.front().is_some_and(|t| now.duration_since(*t) > self.window) {
+            self.failures.pop_front();
+        }
+        if !self.open && self.failures.len() >= self.max_failures {
+            self.open = true;
+            return true;
+        }
+        false
+    }
+}
+
+/// Default write payload size: the minimum BLE ATT MTU (23) minus the 3-byte ATT header
+pub const DEFAULT_MTU: usize = 20;
+
+/// Bytes at the start of every chunk: a big-endian `u16` sequence number and a flags byte
+pub const FRAME_HEADER_LEN: usize = 3;
+
+/// Frame flag marking the last chunk of a payload
+pub const FRAME_FLAG_FINAL: u8 = 0x01;
+
+/// Split a payload into framed chunks of at most `mtu` bytes
+///
+/// Sequence numbers start at zero for each payload and the last chunk carries
+/// `FRAME_FLAG_FINAL`, so the receiver can reassemble with `FrameAssembler`.
+pub fn frame_chunks(data: &[u8], mtu: usize) -> Result<Vec<Vec<u8>>> {
+    if mtu <= FRAME_HEADER_LEN {
+        return Err(CryptoNodeError::InvalidInput(format!("MTU must exceed the {}-byte frame header", FRAME_HEADER_LEN)));
+    }
+    let chunks: Vec<&[u8]> = data.chunks(mtu - FRAME_HEADER_LEN).collect();
+    if chunks.len() > u16::MAX as usize + 1 {
+        return Err(CryptoNodeError::InvalidInput("Payload needs more chunks than the sequence number allows".to_string()));
+    }
+
+    let last = chunks.len().saturating_sub(1);
+    Ok(chunks.into_iter().enumerate().map(|(seq, chunk)| {
+        let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + chunk.len());
+        frame.extend_from_slice(&(seq as u16).to_be_bytes());
+        frame.push(if seq == last { FRAME_FLAG_FINAL } else { 0 });
+        frame.extend_from_slice(chunk);
+        frame
+    }).collect())
+}
+
+/// How long a partial payload may wait for its remaining frames by default
+pub const DEFAULT_REASSEMBLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reassembles payloads from frames produced by `frame_chunks`
+#[derive(Debug)]
+pub struct FrameAssembler {
+    buffer: Vec<u8>,
+    next_seq: u16,
+    started: Option<Instant>, // When the partial payload's first frame arrived
+    timeout: Option<Duration>,
+}
+
+impl Default for FrameAssembler {
+    fn default() -> Self {
+        Self {
+            buffer: Vec::new(),
+            next_seq: 0,
+            started: None,
+            timeout: Some(DEFAULT_REASSEMBLY_TIMEOUT),
+        }
+    }
+}
+
+impl FrameAssembler {
+    /// Create an empty assembler
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Discard partial payloads not completed within `timeout`, or never with `None`
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Add a received frame, returning the payload once its final frame arrives
+    ///
+    /// A frame out of sequence discards the partial payload and is an error;
+    /// a frame with sequence zero always starts a new payload. A frame that
+    /// continues a timed-out payload fails with `Timeout`.
+    pub fn push(&mut self, frame: &[u8]) -> Result<Option<Vec<u8>>> {
+        if frame.len() < FRAME_HEADER_LEN {
+            return Err(CryptoNodeError::InvalidInput("Frame is shorter than its header".to_string()));
+        }
+        let seq = u16::from_be_bytes([frame[0], frame[1]]);
+        let flags = frame[2];
+
+        if self.expire().is_some() && seq != 0 {
+            return Err(CryptoNodeError::Timeout);
+        }
+        if seq == 0 {
+            self.buffer.clear();
+            self.started = Some(Instant::now());
+        } else if seq != self.next_seq {
+            let expected = self.next_seq;
+            self.reset();
+            return Err(CryptoNodeError::InvalidInput(format!("Expected frame {}, got {}", expected, seq)));
+        }
+
+        self.buffer.extend_from_slice(&frame[FRAME_HEADER_LEN..]);
+        if flags & FRAME_FLAG_FINAL != 0 {
+            self.next_seq = 0;
+            self.started = None;
+            return Ok(Some(std::mem::take(&mut self.buffer)));
+        }
+        self.next_seq = seq.wrapping_add(1);
+        Ok(None)
+    }
+
+    /// Discard a partial payload that has outlived the timeout
+    ///
+    /// Returns the `BluetoothEvent::Error` to emit when one was discarded.
+    /// Call periodically so a payload whose last frames never arrive
+    /// doesn't sit in the buffer until the next frame.
+    pub fn expire(&mut self) -> Option<BluetoothEvent> {
+        let (started, timeout) = (self.started?, self.timeout?);
+        if started.elapsed() < timeout {
+            return None;
+        }
+        self.reset();
+        Some(BluetoothEvent::Error("reassembly timeout".to_string()))
+    }
+
+    /// Discard any partially received payload
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.next_seq = 0;
+        self.started = None;
+    }
+}
+
 /// Represents a Bluetooth connection manager
 pub struct BluetoothManager {
-    adapter: Adapter,
+    adapter: Arc<RwLock<Adapter>>,
     characteristics: Arc<RwLock<Vec<Characteristic>>>,
     connected_device: Arc<RwLock<Option<Peripheral>>>,
     event_sender: mpsc::Sender<BluetoothEvent>,
+    mtu: usize,
+    retry_policy: RetryPolicy,
+    dropped_events: Arc<AtomicU64>,
+    status: Arc<RwLock<ConnectionStatus>>,
+    scan_task: RwLock<Option<JoinHandle<()>>>,
+    scanning: Arc<AtomicBool>,
+    last_scan_event: Arc<RwLock<Instant>>,
+    scan_watchdog: Option<Duration>, // Restart a scan that is silent for this long
+    auto_subscribe: Vec<CharacteristicRole>, // Subscribed to automatically on connect
+    reconnect_breaker: RwLock<Option<ReconnectBreaker>>,
+    connect_policy: ConnectPolicy,
+    unknown_command_policy: UnknownCommandPolicy,
+    watchdog_task: RwLock<Option<JoinHandle<()>>>,
+    discovered: Arc<RwLock<HashMap<String, DiscoveredDevice>>>,
+    max_discovered: Arc<AtomicUsize>,
+    discovery_pruning: DiscoveryPruning,
+    notification_task: RwLock<Option<JoinHandle<()>>>,
+    error_streak: ErrorStreak, // Consecutive write/subscribe errors, for adapter resets
+}
+
+/// Details of the currently connected peripheral
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectedDeviceInfo {
+    pub id: String,
+    pub name: Option<String>,
+    pub rssi: Option<i16>,
+    pub write_mtu: usize, // Write size set by `set_mtu`; btleplug doesn't expose the negotiated MTU
+}
+
+impl ConnectedDeviceInfo {
+    /// Describe a device from whatever properties the adapter reported
+    fn new(id: String, props: Option<PeripheralProperties>, write_mtu: usize) -> Self {
+        let (name, rssi) = match props {
+            Some(props) => (props.local_name, props.rssi),
+            None => (None, None),
+        };
+        Self { id, name, rssi, write_mtu }
+    }
 }
 
 /// Events that can occur during Bluetooth operation
@@ -35,6 +429,10 @@ pub enum BluetoothEvent {
     DeviceDisconnected(String),
     DataReceived(Vec<u8>),
     Error(String),
+    /// The scan watchdog restarted a scan that stopped producing events
+    ScanRestarted,
+    /// The adapter was re-initialized after repeated errors
+    AdapterReset,
 }
 
 impl BluetoothManager {
@@ -48,39 +446,104 @@ impl BluetoothManager {
         let (tx, rx) = mpsc::channel(100);
 
         Ok((Self {
-            adapter,
+            adapter: Arc::new(RwLock::new(adapter)),
             characteristics: Arc::new(RwLock::new(Vec::new())),
             connected_device: Arc::new(RwLock::new(None)),
             event_sender: tx,
+            mtu: DEFAULT_MTU,
+            retry_policy: RetryPolicy::default(),
+            dropped_events: Arc::new(AtomicU64::new(0)),
+            status: Arc::new(RwLock::new(ConnectionStatus::Disconnected)),
+            scan_task: RwLock::new(None),
+            scanning: Arc::new(AtomicBool::new(false)),
+            last_scan_event: Arc::new(RwLock::new(Instant::now())),
+            scan_watchdog: None,
+            auto_subscribe: Vec::new(),
+            reconnect_breaker: RwLock::new(None),
+            connect_policy: ConnectPolicy::default(),
+            unknown_command_policy: UnknownCommandPolicy::default(),
+            watchdog_task: RwLock::new(None),
+            discovered: Arc::new(RwLock::new(HashMap::new())),
+            max_discovered: Arc::new(AtomicUsize::new(DEFAULT_MAX_DISCOVERED)),
+            discovery_pruning: DiscoveryPruning::default(),
+            notification_task: RwLock::new(None),
+            error_streak: ErrorStreak::default(),
         }, rx))
     }
 
     /// Start scanning for devices
+    ///
+    /// The adapter event listener outlives individual scans, so calling this
+    /// again restarts the scan without spawning a second listener.
     pub async fn start_scan(&self) -> Result<()> {
-        self.adapter
+        let adapter = self.adapter.read().await.clone();
+        adapter
             .start_scan(ScanFilter::default())
             .await
             .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))?;
+        self.scanning.store(true, Ordering::SeqCst);
+        *self.last_scan_event.write().await = Instant::now();
+        self.start_scan_watchdog().await;
+
+        let mut scan_task = self.scan_task.write().await;
+        if task_running(&scan_task) {
+            return Ok(());
+        }
 
         let event_sender = self.event_sender.clone();
-        let adapter = self.adapter.clone();
+        let status = self.status.clone();
+        let discovered = self.discovered.clone();
+        let max_discovered = self.max_discovered.clone();
+        let last_scan_event = self.last_scan_event.clone();
 
-        tokio::spawn(async move {
-            let mut events = adapter.events().await.unwrap();
+        *scan_task = Some(tasks::spawn("bluetooth-scan-listener", async move {
+            let mut events = match adapter.events().await {
+                Ok(events) => events,
+                Err(e) => {
+                    let _ = event_sender.send(BluetoothEvent::Error(e.to_string())).await;
+                    return;
+                }
+            };
             while let Some(event) = events.next().await {
+                if matches!(event, CentralEvent::DeviceDiscovered(_) | CentralEvent::DeviceUpdated(_)) {
+                    *last_scan_event.write().await = Instant::now();
+                }
                 match event {
                     CentralEvent::DeviceDiscovered(id) => {
                         if let Ok(device) = adapter.peripheral(&id).await {
-                            if let Ok(props) = device.properties().await {
+                            if let Ok(Some(props)) = device.properties().await {
+                                let entry = DiscoveredDevice {
+                                    id: id.to_string(),
+                                    name: props.local_name.clone(),
+                                    rssi: props.rssi,
+                                    last_seen: Utc::now(),
+                                    stale: false,
+                                };
+                                record_discovery(&discovered, entry, max_discovered.load(Ordering::Relaxed)).await;
                                 if let Some(name) = props.local_name {
                                     let _ = event_sender.send(BluetoothEvent::DeviceDiscovered(name)).await;
                                 }
                             }
                         }
                     }
+                    CentralEvent::DeviceUpdated(id) => {
+                        // Refresh last-seen time and signal strength of cached devices
+                        if let Ok(device) = adapter.peripheral(&id).await {
+                            if let Ok(Some(props)) = device.properties().await {
+                                let entry = DiscoveredDevice {
+                                    id: id.to_string(),
+                                    name: props.local_name,
+                                    rssi: props.rssi,
+                                    last_seen: Utc::now(),
+                                    stale: false,
+                                };
+                                record_discovery(&discovered, entry, max_discovered.load(Ordering::Relaxed)).await;
+                            }
+                        }
+                    }
                     CentralEvent::DeviceConnected(id) => {
                         if let Ok(device) = adapter.peripheral(&id).await {
-                            if let Ok(props) = device.properties().await {
+                            if let Ok(Some(props)) = device.properties().await {
                                 if let Some(name) = props.local_name {
                                     let _ = event_sender.send(BluetoothEvent::DeviceConnected(name)).await;
                                 }
@@ -89,7 +552,7 @@ impl BluetoothManager {
                     }
                     CentralEvent::DeviceDisconnected(id) => {
                         if let Ok(device) = adapter.peripheral(&id).await {
-                            if let Ok(props) = device.properties().await {
+                            if let Ok(Some(props)) = device.properties().await {
                                 if let Some(name) = props.local_name {
                                     let _ = event_sender.send(BluetoothEvent::DeviceDisconnected(name)).await;
                                 }
@@ -99,52 +562,350 @@ impl BluetoothManager {
                     _ => {}
                 }
             }
-        });
+
+            // The event stream only ends when the adapter goes away, e.g. a dongle is unplugged
+            report_adapter_lost(&status, &event_sender).await;
+        }));
 
         Ok(())
     }
 
+    /// Stop scanning for devices
+    ///
+    /// The discovered-devices cache is pruned per `set_discovery_pruning`.
+    pub async fn stop_scan(&self) -> Result<()> {
+        self.scanning.store(false, Ordering::SeqCst);
+        self.prune_discoveries().await;
+        self.adapter.read().await.stop_scan().await
+            .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))
+    }
+
+    /// Choose how the discovery cache is pruned when a scan stops
+    pub fn set_discovery_pruning(&mut self, pruning: DiscoveryPruning) {
+        self.discovery_pruning = pruning;
+    }
+
+    /// Apply the discovery pruning policy to the cache
+    async fn prune_discoveries(&self) {
+        prune_discoveries(&mut *self.discovered.write().await, self.discovery_pruning);
+    }
+
+    /// Restart scans that see no discovery events for `period`
+    ///
+    /// Some platforms silently stop delivering scan results; `None` disables
+    /// the watchdog. Takes effect on the next `start_scan`.
+    pub fn set_scan_watchdog(&mut self, period: Option<Duration>) -> Result<()> {
+        if period.is_some_and(|p| p.is_zero()) {
+            return Err(CryptoNodeError::InvalidInput("Scan watchdog period cannot be zero".to_string()));
+        }
+        self.scan_watchdog = period;
+        Ok(())
+    }
+
+    /// Spawn the scan watchdog if configured and not already running
+    async fn start_scan_watchdog(&self) {
+        let Some(period) = self.scan_watchdog else {
+            return;
+        };
+        let mut watchdog_task = self.watchdog_task.write().await;
+        if task_running(&watchdog_task) {
+            return;
+        }
+
+        *watchdog_task = Some(spawn_scan_watchdog(
+            self.adapter.clone(),
+            self.scanning.clone(),
+            self.last_scan_event.clone(),
+            self.event_sender.clone(),
+            period,
+        ));
+    }
+
+    /// Scan for a fixed duration, then stop
+    ///
+    /// If the returned future is dropped before it completes, the adapter
+    /// scan is still stopped so the radio isn't left scanning.
+    pub async fn start_scan_for(&self, duration: Duration) -> Result<()> {
+        self.start_scan().await?;
+        let guard = ScanGuard {
+            adapter: Some(self.adapter.read().await.clone()),
+            scanning: self.scanning.clone(),
+        };
+
+        tokio::time::sleep(duration).await;
+
+        let result = guard.stop().await;
+        self.prune_discoveries().await;
+        result
+    }
+
+    /// Devices seen while scanning, most recently seen first
+    pub async fn list_discovered_devices(&self) -> Vec<DiscoveredDevice> {
+        let mut devices: Vec<DiscoveredDevice> = self.discovered.read().await.values().cloned().collect();
+        devices.sort_by_key(|device| std::cmp::Reverse(device.last_seen));
+        devices
+    }
+
+    /// Number of devices currently in the discovery cache
+    pub async fn discovered_device_count(&self) -> usize {
+        self.discovered.read().await.len()
+    }
+
+    /// Limit how many discovered devices are cached
+    ///
+    /// When exceeded, the least recently seen devices are evicted first,
+    /// with the weaker signal going first on ties.
+    pub async fn set_max_discovered_devices(&self, max: usize) -> Result<()> {
+        if max == 0 {
+            return Err(CryptoNodeError::InvalidInput("Discovery cache size cannot be zero".to_string()));
+        }
+        self.max_discovered.store(max, Ordering::Relaxed);
+        evict_discoveries(&mut *self.discovered.write().await, max);
+        Ok(())
+    }
+
     /// Connect to a specific device
+    ///
+    /// Fails immediately while the reconnect circuit is open.
     pub async fn connect_to_device(&self, device: Peripheral) -> Result<()> {
-        device.connect().await
-            .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))?;
+        if self.reconnect_breaker.read().await.as_ref().is_some_and(|b| b.open) {
+            return Err(CryptoNodeError::Bluetooth("reconnect circuit open".to_string()));
+        }
 
-        device.discover_services().await
-            .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))?;
+        let existing = self.connected_device.read().await.as_ref().map(|d| d.id());
+        if self.connect_policy.must_disconnect(existing.as_ref(), &device.id())? {
+            self.disconnect().await?;
+        }
+
+        let peripheral = &device;
+        let connected = retry_with_policy(&self.retry_policy, || async move {
+            peripheral.connect().await
+                .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))
+        }).await;
+        if let Err(e) = connected {
+            let opened = self.reconnect_breaker.write().await
+                .as_mut()
+                .is_some_and(|b| b.record_failure());
+            if opened {
+                warn!("Too many failed connection attempts, stopping reconnects");
+                let _ = self.event_sender.send(BluetoothEvent::Error("reconnect circuit open".to_string())).await;
+            }
+            return Err(e);
+        }
+
+        // From here on the peripheral is connected, so every failure must disconnect it
+        if let Err(e) = device.discover_services().await {
+            let _ = device.disconnect().await;
+            return Err(CryptoNodeError::Bluetooth(e.to_string()));
+        }
 
-        let chars = device.characteristics();
-        let mut characteristics = self.characteristics.write().await;
-        *characteristics = chars.into_iter()
+        let chars: Vec<Characteristic> = device.characteristics().into_iter()
             .filter(|c| CHARACTERISTIC_UUIDS.contains(&c.uuid))
             .collect();
+        *self.characteristics.write().await = chars.clone();
+        *self.connected_device.write().await = Some(device.clone());
+        *self.status.write().await = ConnectionStatus::Connected;
+
+        // Recorded first, so the listener is stopped by `disconnect` like any other
+        if !self.auto_subscribe.is_empty() {
+            if let Err(e) = self.subscribe_roles(&device, &chars).await {
+                let _ = self.disconnect().await;
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Set how connecting behaves while another device is connected
+    pub fn set_connect_policy(&mut self, policy: ConnectPolicy) {
+        self.connect_policy = policy;
+    }
+
+    /// Set how `receive_command` handles bytes that aren't a known command
+    pub fn set_unknown_command_policy(&mut self, policy: UnknownCommandPolicy) {
+        self.unknown_command_policy = policy;
+    }
+
+    /// Decode a command received from the controller
+    ///
+    /// Returns `None` when the bytes aren't a known command, after applying
+    /// the `UnknownCommandPolicy`.
+    pub async fn receive_command(&self, data: &[u8]) -> Result<Option<DeviceCommand>> {
+        let err = match DeviceCommand::parse(data) {
+            Ok(command) => return Ok(Some(command)),
+            Err(e) => e,
+        };
 
-        let mut connected = self.connected_device.write().await;
-        *connected = Some(device);
+        match self.unknown_command_policy.action(&err)? {
+            UnknownCommandAction::Drop => {}
+            UnknownCommandAction::Emit(event) => {
+                let _ = self.event_sender.send(event).await;
+            }
+            UnknownCommandAction::Respond(response) => self.send_response(&response).await?,
+        }
+        Ok(None)
+    }
 
+    /// Stop reconnecting after `max_failures` failed connect cycles within `window`
+    ///
+    /// Once open, the circuit stays open until `reset_reconnect` is called.
+    /// `None` disables the breaker.
+    pub async fn set_reconnect_circuit_breaker(&self, limit: Option<(usize, Duration)>) -> Result<()> {
+        let breaker = match limit {
+            Some((0, _)) => {
+                return Err(CryptoNodeError::InvalidInput("Reconnect failure limit cannot be zero".to_string()));
+            }
+            Some((max_failures, window)) => Some(ReconnectBreaker {
+                max_failures,
+                window,
+                failures: VecDeque::new(),
+                open: false,
+            }),
+            None => None,
+        };
+        *self.reconnect_breaker.write().await = breaker;
         Ok(())
     }
 
+    /// Close the reconnect circuit and forget past failures
+    pub async fn reset_reconnect(&self) {
+        if let Some(breaker) = self.reconnect_breaker.write().await.as_mut() {
+            breaker.failures.clear();
+            breaker.open = false;
+        }
+    }
+
+    /// Whether reconnects are currently blocked by the circuit breaker
+    pub async fn is_reconnect_circuit_open(&self) -> bool {
+        self.reconnect_breaker.read().await.as_ref().is_some_and(|b| b.open)
+    }
+
+    /// Characteristic roles to subscribe to as soon as a device connects
+    pub fn set_auto_subscribe(&mut self, roles: Vec<CharacteristicRole>) {
+        self.auto_subscribe = roles;
+    }
+
+    /// Subscribe to the configured roles that support notifications
+    async fn subscribe_roles(&self, device: &Peripheral, characteristics: &[Characteristic]) -> Result<()> {
+        let mut subscribed = Vec::new();
+        for (role, characteristic) in subscribable_roles(&self.auto_subscribe, characteristics) {
+            device.subscribe(characteristic).await
+                .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))?;
+            subscribed.push(role);
+        }
+
+        if !subscribed.is_empty() {
+            info!("Auto-subscribed to {:?}", subscribed);
+            self.spawn_notification_listener(device.clone()).await;
+        }
+        Ok(())
+    }
+
+    /// Set the maximum size of a single write, frame header included
+    pub fn set_mtu(&mut self, mtu: usize) -> Result<()> {
+        if mtu <= FRAME_HEADER_LEN {
+            return Err(CryptoNodeError::InvalidInput(format!("MTU must exceed the {}-byte frame header", FRAME_HEADER_LEN)));
+        }
+        self.mtu = mtu;
+        Ok(())
+    }
+
+    /// Set the retry policy used for connects and writes
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
     /// Send data to the connected device
+    ///
+    /// Payloads are split into framed writes of at most the MTU; see `frame_chunks`.
     pub async fn send_data(&self, data: &[u8]) -> Result<()> {
+        if data.is_empty() {
+            return Err(CryptoNodeError::InvalidInput("Cannot send an empty payload".to_string()));
+        }
+
+        let result = write_chunked(
+            &self.connected_device,
+            &self.characteristics,
+            CHARACTERISTIC_UUIDS[0],
+            data,
+            self.mtu,
+            &self.retry_policy,
+        ).await;
+        self.record_operation_result(result.is_err()).await;
+        result
+    }
+
+    /// Write a command response to the connected device's response characteristic
+    pub async fn send_response(&self, data: &[u8]) -> Result<()> {
+        if data.is_empty() {
+            return Err(CryptoNodeError::InvalidInput("Cannot send an empty payload".to_string()));
+        }
+
+        let result = write_chunked(
+            &self.connected_device,
+            &self.characteristics,
+            CHARACTERISTIC_UUIDS[1],
+            data,
+            self.mtu,
+            &self.retry_policy,
+        ).await;
+        self.record_operation_result(result.is_err()).await;
+        result
+    }
+
+    /// Periodically push telemetry frames to the connected device
+    ///
+    /// Each frame is JSON-encoded and written to the response characteristic.
+    /// Ticks with no connected device or a failing provider are skipped and
+    /// reported as `BluetoothEvent::Error`.
+    pub fn start_telemetry(&self, interval: Duration, provider: Arc<dyn TelemetryProvider>) -> TelemetryHandle {
+        let connected_device = self.connected_device.clone();
+        let characteristics = self.characteristics.clone();
+        let event_sender = self.event_sender.clone();
+        let mtu = self.mtu;
+        let retry_policy = self.retry_policy;
+
+        let task = tasks::spawn("bluetooth-telemetry", async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+
+                let result = send_telemetry(
+                    &connected_device,
+                    &characteristics,
+                    provider.as_ref(),
+                    mtu,
+                    &retry_policy,
+                ).await;
+                if let Err(e) = result {
+                    let _ = event_sender.try_send(BluetoothEvent::Error(format!("Telemetry failed: {}", e)));
+                }
+            }
+        });
+
+        TelemetryHandle { task }
+    }
+
+    /// List the connected device's characteristics that support notify or indicate
+    pub async fn notifiable_characteristics(&self) -> Result<Vec<Uuid>> {
         let device = self.connected_device.read().await;
         let device = device.as_ref()
             .ok_or_else(|| CryptoNodeError::Bluetooth("No device connected".to_string()))?;
 
-        let characteristics = self.characteristics.read().await;
-        let command_char = characteristics.iter()
-            .find(|c| c.uuid == CHARACTERISTIC_UUIDS[0])
-            .ok_or_else(|| CryptoNodeError::Bluetooth("Command characteristic not found".to_string()))?;
-
-        device.write(command_char, data, WriteType::WithResponse).await
-            .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))?;
-
-        Ok(())
+        Ok(device.characteristics()
+            .iter()
+            .filter(|c| supports_notifications(c))
+            .map(|c| c.uuid)
+            .collect())
     }
 
     /// Subscribe to notifications from the device
+    ///
+    /// Any previous notification listener is stopped and replaced.
     pub async fn subscribe_notifications(&self) -> Result<()> {
-        let device = self.connected_device.read().await;
-        let device = device.as_ref()
+        let connected = self.connected_device.read().await;
+        let device = connected.as_ref()
             .ok_or_else(|| CryptoNodeError::Bluetooth("No device connected".to_string()))?;
 
         let characteristics = self.characteristics.read().await;
@@ -152,29 +913,856 @@ impl BluetoothManager {
             .find(|c| c.uuid == CHARACTERISTIC_UUIDS[2])
             .ok_or_else(|| CryptoNodeError::Bluetooth("Notification characteristic not found".to_string()))?;
 
-        device.subscribe(notify_char).await
-            .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))?;
+        if !supports_notifications(notify_char) {
+            return Err(CryptoNodeError::Device(format!(
+                "Characteristic {} does not support notify or indicate", notify_char.uuid
+            )));
+        }
+
+        let subscribed = device.subscribe(notify_char).await
+            .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()));
+        if subscribed.is_ok() {
+            self.spawn_notification_listener(device.clone()).await;
+        }
+
+        // A reset disconnects, so the device locks must be released first
+        drop(characteristics);
+        drop(connected);
+        self.record_operation_result(subscribed.is_err()).await;
+        subscribed
+    }
+
+    /// Reset the adapter after `threshold` consecutive write or subscribe errors
+    ///
+    /// `None` disables automatic resets.
+    pub fn set_adapter_reset_threshold(&mut self, threshold: Option<u32>) -> Result<()> {
+        if threshold == Some(0) {
+            return Err(CryptoNodeError::InvalidInput("Adapter reset threshold cannot be zero".to_string()));
+        }
+        self.error_streak.threshold = threshold;
+        Ok(())
+    }
+
+    /// Track consecutive operation errors and reset the adapter at the threshold
+    async fn record_operation_result(&self, failed: bool) {
+        let Some(errors) = self.error_streak.record(failed) else {
+            return;
+        };
+
+        warn!("{} consecutive Bluetooth errors, resetting adapter", errors);
+        let result = self.reset_adapter().await;
+        self.error_streak.finish_reset();
+
+        let event = match result {
+            Ok(()) => BluetoothEvent::AdapterReset,
+            Err(e) => BluetoothEvent::Error(format!("Adapter reset failed: {}", e)),
+        };
+        let _ = self.event_sender.send(event).await;
+    }
+
+    /// Stop scanning, disconnect and re-acquire the adapter from the system
+    ///
+    /// Scanning is restarted on the new adapter if it was active.
+    pub async fn reset_adapter(&self) -> Result<()> {
+        let was_scanning = self.scanning.load(Ordering::SeqCst);
+        let _ = self.stop_scan().await;
+        let _ = self.disconnect().await;
+        // The listener is bound to the old adapter's event stream
+        if let Some(task) = self.scan_task.write().await.take() {
+            task.abort();
+        }
+
+        let manager = Manager::new().await.map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))?;
+        let adapters = manager.adapters().await.map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))?;
+        let adapter = adapters.into_iter().next()
+            .ok_or_else(|| CryptoNodeError::Bluetooth("No Bluetooth adapter found".to_string()))?;
+        *self.adapter.write().await = adapter;
+
+        if was_scanning {
+            self.start_scan().await?;
+        }
+        info!("Bluetooth adapter reset");
+        Ok(())
+    }
 
+    /// Forward the device's notifications as events, replacing any previous listener
+    async fn spawn_notification_listener(&self, device: Peripheral) {
         let event_sender = self.event_sender.clone();
-        let device_clone = device.clone();
-        
-        tokio::spawn(async move {
-            let mut notification_stream = device_clone.notifications().await.unwrap();
-            while let Some(data) = notification_stream.next().await {
-                let _ = event_sender.send(BluetoothEvent::DataReceived(data.value)).await;
-            }
+        let dropped_events = self.dropped_events.clone();
+
+        let task = tasks::spawn("bluetooth-notifications", async move {
+            let notification_stream = match device.notifications().await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("Failed to open notification stream: {}", e);
+                    let _ = event_sender.try_send(BluetoothEvent::Error(format!("Notification stream failed: {}", e)));
+                    return;
+                }
+            };
+            forward_notifications(notification_stream.map(|n| n.value), &event_sender, &dropped_events).await;
         });
+        replace_task(&mut *self.notification_task.write().await, task);
+    }
 
-        Ok(())
+    /// Describe the connected device, or `None` when nothing is connected
+    pub async fn connected_device_info(&self) -> Result<Option<ConnectedDeviceInfo>> {
+        let device = self.connected_device.read().await;
+        let Some(device) = device.as_ref() else {
+            return Ok(None);
+        };
+
+        let props = device.properties().await
+            .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))?;
+        Ok(Some(ConnectedDeviceInfo::new(device.id().to_string(), props, self.mtu)))
+    }
+
+    /// Get the current connection status
+    pub async fn connection_status(&self) -> ConnectionStatus {
+        *self.status.read().await
+    }
+
+    /// Number of notifications dropped because the event channel was full
+    pub fn dropped_event_count(&self) -> u64 {
+        self.dropped_events.load(Ordering::Relaxed)
     }
 
     /// Disconnect from the current device
     pub async fn disconnect(&self) -> Result<()> {
+        if let Some(task) = self.notification_task.write().await.take() {
+            task.abort();
+        }
         let mut device = self.connected_device.write().await;
         if let Some(d) = device.take() {
             d.disconnect().await
                 .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))?;
+            *self.status.write().await = ConnectionStatus::Disconnected;
         }
         Ok(())
     }
-} 
\ No newline at end of file
+}
+
+/// Mark the adapter as failed and tell the consumer it is gone
+async fn report_adapter_lost(status: &RwLock<ConnectionStatus>, event_sender: &mpsc::Sender<BluetoothEvent>) {
+    *status.write().await = ConnectionStatus::Error;
+    let _ = event_sender.send(BluetoothEvent::Error("adapter lost".to_string())).await;
+}
+
+/// Forward notification payloads as events until the stream ends or the receiver closes
+///
+/// Never blocks the BLE stream on a slow consumer; payloads that don't fit
+/// in the channel are dropped and counted in `dropped_events`.
+async fn forward_notifications(
+    mut notifications: impl futures::Stream<Item = Vec<u8>> + Unpin,
+    event_sender: &mpsc::Sender<BluetoothEvent>,
+    dropped_events: &AtomicU64,
+) {
+    while let Some(data) = notifications.next().await {
+        match event_sender.try_send(BluetoothEvent::DataReceived(data)) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                dropped_events.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(TrySendError::Closed(_)) => break,
+        }
+    }
+}
+
+/// Insert or refresh a discovered device, keeping the cache within `max`
+async fn record_discovery(
+    discovered: &RwLock<HashMap<String, DiscoveredDevice>>,
+    device: DiscoveredDevice,
+    max: usize,
+) {
+    let mut discovered = discovered.write().await;
+    discovered.insert(device.id.clone(), device);
+    evict_discoveries(&mut discovered, max);
+}
+
+/// Drop the least relevant devices until at most `max` remain
+fn evict_discoveries(discovered: &mut HashMap<String, DiscoveredDevice>, max: usize) {
+    if discovered.len() <= max {
+        return;
+    }
+
+    let mut ranked: Vec<(DateTime<Utc>, i16, String)> = discovered.values()
+        .map(|d| (d.last_seen, d.rssi.unwrap_or(i16::MIN), d.id.clone()))
+        .collect();
+    ranked.sort();
+    let excess = discovered.len() - max;
+    for (_, _, id) in ranked.into_iter().take(excess) {
+        discovered.remove(&id);
+    }
+}
+
+/// Prune the discovery cache per `pruning`, marking what remains stale
+fn prune_discoveries(discovered: &mut HashMap<String, DiscoveredDevice>, pruning: DiscoveryPruning) {
+    match pruning {
+        DiscoveryPruning::MarkStale => {}
+        DiscoveryPruning::Clear => discovered.clear(),
+        DiscoveryPruning::AgeOut(age) => {
+            let cutoff = chrono::Duration::from_std(age).ok()
+                .and_then(|age| Utc::now().checked_sub_signed(age));
+            if let Some(cutoff) = cutoff {
+                discovered.retain(|_, device| device.last_seen >= cutoff);
+            }
+        }
+    }
+    for device in discovered.values_mut() {
+        device.stale = true;
+    }
+}
+
+/// Pair each role with its characteristic, skipping roles that can't notify
+fn subscribable_roles<'a>(
+    roles: &[CharacteristicRole],
+    characteristics: &'a [Characteristic],
+) -> Vec<(CharacteristicRole, &'a Characteristic)> {
+    let mut subscribable = Vec::new();
+    for role in roles {
+        let Some(characteristic) = characteristics.iter().find(|c| c.uuid == role.uuid()) else {
+            warn!("Auto-subscribe skipped {:?}: characteristic not found", role);
+            continue;
+        };
+        if !supports_notifications(characteristic) {
+            warn!("Auto-subscribe skipped {:?}: notifications not supported", role);
+            continue;
+        }
+        subscribable.push((*role, characteristic));
+    }
+    subscribable
+}
+
+/// Whether a tracked task is still doing its work
+fn task_running(slot: &Option<JoinHandle<()>>) -> bool {
+    slot.as_ref().is_some_and(|task| !task.is_finished())
+}
+
+/// Track `task` in `slot`, stopping the task it replaces
+fn replace_task(slot: &mut Option<JoinHandle<()>>, task: JoinHandle<()>) {
+    if let Some(previous) = slot.replace(task) {
+        previous.abort();
+    }
+}
+
+/// Whether a characteristic can push values via notify or indicate
+fn supports_notifications(characteristic: &Characteristic) -> bool {
+    characteristic.properties.intersects(CharPropFlags::NOTIFY | CharPropFlags::INDICATE)
+}
+
+/// An adapter whose scan a `ScanGuard` can stop
+trait HaltScan: Send + 'static {
+    fn halt_scan(self) -> BoxFuture<'static, Result<()>>;
+}
+
+impl HaltScan for Adapter {
+    fn halt_scan(self) -> BoxFuture<'static, Result<()>> {
+        Box::pin(async move {
+            self.stop_scan().await
+                .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))
+        })
+    }
+}
+
+/// An adapter whose scan the watchdog can restart
+trait RestartScan: Clone + Send + Sync + 'static {
+    fn restart_scan(self) -> BoxFuture<'static, Result<()>>;
+}
+
+impl RestartScan for Adapter {
+    fn restart_scan(self) -> BoxFuture<'static, Result<()>> {
+        Box::pin(async move {
+            let _ = self.stop_scan().await;
+            self.start_scan(ScanFilter::default()).await
+                .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))
+        })
+    }
+}
+
+/// Restart the scan whenever it has been silent for `period` while it should be running
+fn spawn_scan_watchdog<A: RestartScan>(
+    adapter: Arc<RwLock<A>>,
+    scanning: Arc<AtomicBool>,
+    last_scan_event: Arc<RwLock<Instant>>,
+    event_sender: mpsc::Sender<BluetoothEvent>,
+    period: Duration,
+) -> JoinHandle<()> {
+    tasks::spawn("bluetooth-scan-watchdog", async move {
+        let mut ticker = tokio::time::interval(period);
+        loop {
+            ticker.tick().await;
+            if !scanning.load(Ordering::SeqCst) {
+                continue;
+            }
+
+            let mut last_event = last_scan_event.write().await;
+            if last_event.elapsed() < period {
+                continue;
+            }
+
+            warn!("No scan results for {:?}, restarting scan", period);
+            let adapter = adapter.read().await.clone();
+            if let Err(e) = adapter.restart_scan().await {
+                let _ = event_sender.try_send(BluetoothEvent::Error(format!("Scan restart failed: {}", e)));
+            }
+            *last_event = Instant::now();
+            let _ = event_sender.try_send(BluetoothEvent::ScanRestarted);
+        }
+    })
+}
+
+/// Stops an adapter scan when dropped
+struct ScanGuard<A: HaltScan = Adapter> {
+    adapter: Option<A>,
+    scanning: Arc<AtomicBool>,
+}
+
+impl<A: HaltScan> ScanGuard<A> {
+    /// Stop the scan now, surfacing any error
+    async fn stop(mut self) -> Result<()> {
+        self.scanning.store(false, Ordering::SeqCst);
+        match self.adapter.take() {
+            Some(adapter) => adapter.halt_scan().await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl<A: HaltScan> Drop for ScanGuard<A> {
+    fn drop(&mut self) {
+        // Drop can't await, so stop the scan on the runtime if one is available
+        if let Some(adapter) = self.adapter.take() {
+            self.scanning.store(false, Ordering::SeqCst);
+            if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                handle.spawn(async move {
+                    let _ = adapter.halt_scan().await;
+                });
+            }
+        }
+    }
+}
+
+/// Encode one telemetry frame and write it to the response characteristic
+async fn send_telemetry(
+    connected_device: &RwLock<Option<Peripheral>>,
+    characteristics: &RwLock<Vec<Characteristic>>,
+    provider: &dyn TelemetryProvider,
+    mtu: usize,
+    retry_policy: &RetryPolicy,
+) -> Result<()> {
+    let frame = provider.telemetry()?;
+    let bytes = serde_json::to_vec(&frame)
+        .map_err(|e| CryptoNodeError::Serialization(e.to_string()))?;
+    write_chunked(connected_device, characteristics, CHARACTERISTIC_UUIDS[1], &bytes, mtu, retry_policy).await
+}
+
+/// Write a payload to a characteristic of the connected device as framed MTU-sized chunks
+async fn write_chunked(
+    connected_device: &RwLock<Option<Peripheral>>,
+    characteristics: &RwLock<Vec<Characteristic>>,
+    characteristic_uuid: Uuid,
+    data: &[u8],
+    mtu: usize,
+    retry_policy: &RetryPolicy,
+) -> Result<()> {
+    let device = connected_device.read().await;
+    let device = device.as_ref()
+        .ok_or_else(|| CryptoNodeError::Bluetooth("No device connected".to_string()))?;
+
+    let characteristics = characteristics.read().await;
+    let characteristic = characteristics.iter()
+        .find(|c| c.uuid == characteristic_uuid)
+        .ok_or_else(|| CryptoNodeError::Bluetooth(format!("Characteristic {} not found", characteristic_uuid)))?;
+
+    for frame in frame_chunks(data, mtu)? {
+        let frame = &frame;
+        retry_with_policy(retry_policy, || async move {
+            device.write(characteristic, frame, WriteType::WithResponse).await
+                .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))
+        }).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oversized_payload_is_framed_into_mtu_sized_chunks() {
+        let data: Vec<u8> = (0..50).collect();
+        let frames = frame_chunks(&data, DEFAULT_MTU).unwrap();
+
+        assert_eq!(frames.len(), 3);
+        assert!(frames.iter().all(|f| f.len() <= DEFAULT_MTU));
+        assert_eq!(&frames[1][..FRAME_HEADER_LEN], &[0, 1, 0]);
+        assert_eq!(frames[2][2], FRAME_FLAG_FINAL);
+    }
+
+    #[test]
+    fn mtu_must_leave_room_for_payload() {
+        assert!(frame_chunks(b"data", FRAME_HEADER_LEN).is_err());
+    }
+
+    #[test]
+    fn assembler_reassembles_framed_payload() {
+        let data: Vec<u8> = (0..100).collect();
+        let mut assembler = FrameAssembler::new();
+        let mut result = None;
+        for frame in frame_chunks(&data, DEFAULT_MTU).unwrap() {
+            assert!(result.is_none());
+            result = assembler.push(&frame).unwrap();
+        }
+        assert_eq!(result, Some(data));
+    }
+
+    #[test]
+    fn assembler_rejects_gap_and_recovers() {
+        let frames = frame_chunks(&[7u8; 40], DEFAULT_MTU).unwrap();
+        let mut assembler = FrameAssembler::new();
+        assembler.push(&frames[0]).unwrap();
+        assert!(assembler.push(&frames[2]).is_err());
+
+        let mut result = None;
+        for frame in &frames {
+            result = assembler.push(frame).unwrap();
+        }
+        assert_eq!(result, Some(vec![7u8; 40]));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn assembler_discards_payload_missing_its_final_frame() {
+        let frames = frame_chunks(&[7u8; 40], DEFAULT_MTU).unwrap();
+        let mut assembler = FrameAssembler::new();
+        assembler.set_timeout(Some(Duration::from_secs(5)));
+        assembler.push(&frames[0]).unwrap();
+        assembler.push(&frames[1]).unwrap();
+
+        tokio::time::advance(Duration::from_secs(4)).await;
+        assert!(assembler.expire().is_none());
+        tokio::time::advance(Duration::from_secs(1)).await;
+        let event = assembler.expire();
+        assert!(matches!(event, Some(BluetoothEvent::Error(ref message)) if message == "reassembly timeout"));
+
+        // The final frame alone can't complete the discarded payload
+        assert!(assembler.push(&frames[2]).is_err());
+        let mut result = None;
+        for frame in &frames {
+            result = assembler.push(frame).unwrap();
+        }
+        assert_eq!(result, Some(vec![7u8; 40]));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn late_frame_of_timed_out_payload_is_a_timeout() {
+        let frames = frame_chunks(&[7u8; 40], DEFAULT_MTU).unwrap();
+        let mut assembler = FrameAssembler::new();
+        assembler.push(&frames[0]).unwrap();
+
+        tokio::time::advance(DEFAULT_REASSEMBLY_TIMEOUT).await;
+        assert!(matches!(assembler.push(&frames[1]), Err(CryptoNodeError::Timeout)));
+    }
+
+    fn undecodable() -> CryptoNodeError {
+        DeviceCommand::parse(b"\xff not a command").unwrap_err()
+    }
+
+    #[test]
+    fn unknown_command_defaults_to_error_event() {
+        let action = UnknownCommandPolicy::default().action(&undecodable()).unwrap();
+        assert!(matches!(action, UnknownCommandAction::Emit(BluetoothEvent::Error(_))));
+    }
+
+    #[test]
+    fn unknown_command_can_be_ignored() {
+        let action = UnknownCommandPolicy::Ignore.action(&undecodable()).unwrap();
+        assert!(matches!(action, UnknownCommandAction::Drop));
+    }
+
+    #[test]
+    fn unknown_command_can_be_answered() {
+        let action = UnknownCommandPolicy::RespondWithError.action(&undecodable()).unwrap();
+        let UnknownCommandAction::Respond(bytes) = action else {
+            panic!("expected a response");
+        };
+        let response: CommandErrorResponse = serde_json::from_slice(&bytes).unwrap();
+        assert!(response.error.contains("Invalid command"));
+    }
+
+    #[tokio::test]
+    async fn notification_flood_is_dropped_and_counted() {
+        let (sender, mut receiver) = mpsc::channel(4);
+        let dropped = AtomicU64::new(0);
+        let flood = futures::stream::iter((0..100u8).map(|i| vec![i]));
+
+        // Nothing reads until the flood is over, so forwarding must not block
+        tokio::time::timeout(Duration::from_secs(1), forward_notifications(flood, &sender, &dropped))
+            .await
+            .expect("forwarding blocked on a full channel");
+
+        assert_eq!(dropped.load(Ordering::Relaxed), 96);
+        for expected in 0..4u8 {
+            assert!(matches!(receiver.recv().await, Some(BluetoothEvent::DataReceived(data)) if data == vec![expected]));
+        }
+    }
+
+    #[tokio::test]
+    async fn notification_forwarding_stops_when_receiver_closes() {
+        let (sender, receiver) = mpsc::channel(4);
+        drop(receiver);
+        let dropped = AtomicU64::new(0);
+
+        forward_notifications(futures::stream::repeat(vec![0u8]), &sender, &dropped).await;
+        assert_eq!(dropped.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn lost_adapter_sets_error_status_and_reports_it() {
+        let status = RwLock::new(ConnectionStatus::Connected);
+        let (sender, mut receiver) = mpsc::channel(1);
+
+        report_adapter_lost(&status, &sender).await;
+
+        assert_eq!(*status.read().await, ConnectionStatus::Error);
+        assert!(matches!(receiver.recv().await, Some(BluetoothEvent::Error(message)) if message == "adapter lost"));
+    }
+
+    fn characteristic(properties: CharPropFlags) -> Characteristic {
+        Characteristic {
+            uuid: CHARACTERISTIC_UUIDS[2],
+            service_uuid: SERVICE_UUID,
+            properties,
+            descriptors: Default::default(),
+        }
+    }
+
+    #[test]
+    fn notify_or_indicate_counts_as_notification_support() {
+        assert!(supports_notifications(&characteristic(CharPropFlags::NOTIFY)));
+        assert!(supports_notifications(&characteristic(CharPropFlags::INDICATE | CharPropFlags::READ)));
+        assert!(!supports_notifications(&characteristic(CharPropFlags::READ | CharPropFlags::WRITE)));
+    }
+
+    /// Counts requests and fails when `fail` is set
+    struct FakeTelemetry {
+        fail: bool,
+        requests: AtomicUsize,
+    }
+
+    impl TelemetryProvider for FakeTelemetry {
+        fn telemetry(&self) -> Result<TelemetryFrame> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                return Err(CryptoNodeError::Device("battery unreadable".to_string()));
+            }
+            Ok(TelemetryFrame {
+                timestamp: Utc::now(),
+                battery_level: Some(0.5),
+                current_bandwidth: 1024.0,
+                balances: HashMap::from([(Uuid::new_v4(), 2.5)]),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn telemetry_reports_provider_and_connection_failures() {
+        let device = RwLock::new(None);
+        let characteristics = RwLock::new(Vec::new());
+        let policy = RetryPolicy::default();
+
+        let failing = FakeTelemetry { fail: true, requests: AtomicUsize::new(0) };
+        let result = send_telemetry(&device, &characteristics, &failing, DEFAULT_MTU, &policy).await;
+        assert!(matches!(result, Err(CryptoNodeError::Device(_))));
+
+        let working = FakeTelemetry { fail: false, requests: AtomicUsize::new(0) };
+        let result = send_telemetry(&device, &characteristics, &working, DEFAULT_MTU, &policy).await;
+        assert!(matches!(result, Err(CryptoNodeError::Bluetooth(message)) if message == "No device connected"));
+        assert_eq!(working.requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn telemetry_frame_round_trips_as_json() {
+        let provider = FakeTelemetry { fail: false, requests: AtomicUsize::new(0) };
+        let frame = provider.telemetry().unwrap();
+
+        let decoded: TelemetryFrame = serde_json::from_slice(&serde_json::to_vec(&frame).unwrap()).unwrap();
+
+        assert_eq!(decoded.timestamp, frame.timestamp);
+        assert_eq!(decoded.battery_level, Some(0.5));
+        assert_eq!(decoded.balances, frame.balances);
+    }
+
+    /// Counts how often its scan is stopped
+    struct FakeScanner(Arc<AtomicUsize>);
+
+    impl HaltScan for FakeScanner {
+        fn halt_scan(self) -> BoxFuture<'static, Result<()>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelled_timed_scan_still_stops_the_adapter() {
+        let stops = Arc::new(AtomicUsize::new(0));
+        let scanning = Arc::new(AtomicBool::new(true));
+        let scan = {
+            let guard = ScanGuard { adapter: Some(FakeScanner(stops.clone())), scanning: scanning.clone() };
+            async move {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                guard.stop().await
+            }
+        };
+
+        // Dropping the future before the scan finishes stands in for a cancelled caller
+        assert!(tokio::time::timeout(Duration::from_millis(10), scan).await.is_err());
+        tokio::task::yield_now().await;
+
+        assert!(!scanning.load(Ordering::SeqCst));
+        assert_eq!(stops.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn completed_scan_stops_the_adapter_once() {
+        let stops = Arc::new(AtomicUsize::new(0));
+        let scanning = Arc::new(AtomicBool::new(true));
+        let guard = ScanGuard { adapter: Some(FakeScanner(stops.clone())), scanning: scanning.clone() };
+
+        guard.stop().await.unwrap();
+        tokio::task::yield_now().await;
+
+        assert!(!scanning.load(Ordering::SeqCst));
+        assert_eq!(stops.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn replacing_a_tracked_task_stops_the_previous_one() {
+        let mut slot = None;
+        assert!(!task_running(&slot));
+
+        replace_task(&mut slot, tokio::spawn(std::future::pending()));
+        let first = slot.as_ref().unwrap().id();
+        assert!(task_running(&slot));
+
+        replace_task(&mut slot, tokio::spawn(std::future::pending()));
+        tokio::task::yield_now().await;
+
+        assert_ne!(slot.as_ref().unwrap().id(), first);
+        assert!(task_running(&slot));
+        slot.take().unwrap().abort();
+    }
+
+    #[tokio::test]
+    async fn aborted_previous_task_is_cancelled() {
+        let previous = tokio::spawn(std::future::pending::<()>());
+        let abort = previous.abort_handle();
+        let mut slot = Some(previous);
+
+        replace_task(&mut slot, tokio::spawn(async {}));
+        tokio::task::yield_now().await;
+
+        assert!(abort.is_finished());
+        assert!(!task_running(&slot));
+    }
+
+    #[test]
+    fn device_info_uses_reported_name_and_rssi() {
+        let props = PeripheralProperties {
+            local_name: Some("node-7".to_string()),
+            rssi: Some(-60),
+            ..PeripheralProperties::default()
+        };
+
+        let info = ConnectedDeviceInfo::new("hci0/dev_1".to_string(), Some(props), 185);
+        assert_eq!(info.name.as_deref(), Some("node-7"));
+        assert_eq!(info.rssi, Some(-60));
+        assert_eq!(info.write_mtu, 185);
+
+        let unknown = ConnectedDeviceInfo::new("hci0/dev_1".to_string(), None, DEFAULT_MTU);
+        assert_eq!((unknown.name, unknown.rssi), (None, None));
+    }
+
+    fn discovered(id: &str, seconds_ago: i64, rssi: Option<i16>) -> DiscoveredDevice {
+        DiscoveredDevice {
+            id: id.to_string(),
+            name: None,
+            rssi,
+            last_seen: Utc::now() - chrono::Duration::seconds(seconds_ago),
+            stale: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn discovery_cache_evicts_least_recently_seen_devices() {
+        let cache = RwLock::new(HashMap::new());
+        let seen = Utc::now() - chrono::Duration::seconds(30);
+        record_discovery(&cache, discovered("old", 60, Some(-40)), 3).await;
+        record_discovery(&cache, DiscoveredDevice { last_seen: seen, ..discovered("weak", 0, Some(-90)) }, 3).await;
+        record_discovery(&cache, DiscoveredDevice { last_seen: seen, ..discovered("strong", 0, Some(-50)) }, 3).await;
+        record_discovery(&cache, discovered("new", 0, None), 3).await;
+
+        let mut ids: Vec<String> = cache.read().await.keys().cloned().collect();
+        ids.sort();
+        assert_eq!(ids, vec!["new", "strong", "weak"]);
+
+        // Ties on last seen go to the stronger signal
+        record_discovery(&cache, discovered("newer", 0, None), 3).await;
+        assert!(!cache.read().await.contains_key("weak"));
+        assert!(cache.read().await.contains_key("strong"));
+    }
+
+    #[tokio::test]
+    async fn rediscovery_refreshes_instead_of_growing_the_cache() {
+        let cache = RwLock::new(HashMap::new());
+        record_discovery(&cache, discovered("a", 60, Some(-70)), 2).await;
+        record_discovery(&cache, discovered("b", 30, Some(-70)), 2).await;
+        record_discovery(&cache, discovered("a", 0, Some(-60)), 2).await;
+        record_discovery(&cache, discovered("c", 0, None), 2).await;
+
+        let cache = cache.read().await;
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache["a"].rssi, Some(-60));
+        assert!(!cache.contains_key("b"));
+    }
+
+    /// Counts scan restarts
+    #[derive(Clone)]
+    struct FakeRestarts(Arc<AtomicUsize>);
+
+    impl RestartScan for FakeRestarts {
+        fn restart_scan(self) -> BoxFuture<'static, Result<()>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn silent_scan_is_restarted_only_while_scanning() {
+        let restarts = Arc::new(AtomicUsize::new(0));
+        let scanning = Arc::new(AtomicBool::new(false));
+        let last_event = Arc::new(RwLock::new(Instant::now()));
+        let (sender, mut receiver) = mpsc::channel(8);
+        let period = Duration::from_millis(20);
+        let watchdog = spawn_scan_watchdog(
+            Arc::new(RwLock::new(FakeRestarts(restarts.clone()))),
+            scanning.clone(),
+            last_event.clone(),
+            sender,
+            period,
+        );
+
+        tokio::time::sleep(period * 3).await;
+        assert_eq!(restarts.load(Ordering::SeqCst), 0);
+
+        scanning.store(true, Ordering::SeqCst);
+        let event = tokio::time::timeout(Duration::from_secs(1), receiver.recv()).await.unwrap();
+        watchdog.abort();
+
+        assert!(matches!(event, Some(BluetoothEvent::ScanRestarted)));
+        assert!(restarts.load(Ordering::SeqCst) >= 1);
+        assert!(last_event.read().await.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn auto_subscribe_skips_missing_and_non_notifying_roles() {
+        let characteristics = vec![
+            Characteristic { uuid: CHARACTERISTIC_UUIDS[0], ..characteristic(CharPropFlags::WRITE) },
+            Characteristic { uuid: CHARACTERISTIC_UUIDS[2], ..characteristic(CharPropFlags::NOTIFY) },
+        ];
+        let roles = [CharacteristicRole::Command, CharacteristicRole::Response, CharacteristicRole::Notification];
+
+        let subscribable = subscribable_roles(&roles, &characteristics);
+
+        assert_eq!(subscribable.len(), 1);
+        assert_eq!(subscribable[0].0, CharacteristicRole::Notification);
+        assert_eq!(subscribable[0].1.uuid, CHARACTERISTIC_UUIDS[2]);
+    }
+
+    #[tokio::test]
+    async fn breaker_opens_once_failures_cluster_within_the_window() {
+        let mut breaker = ReconnectBreaker {
+            max_failures: 3,
+            window: Duration::from_millis(50),
+            failures: VecDeque::new(),
+            open: false,
+        };
+
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        // The earlier failures have left the window
+        assert!(!breaker.record_failure());
+        assert!(!breaker.open);
+
+        assert!(!breaker.record_failure());
+        assert!(breaker.record_failure());
+        assert!(breaker.open);
+        // Only the failure that opens the circuit reports it
+        assert!(!breaker.record_failure());
+    }
+
+    #[test]
+    fn connect_policy_only_acts_on_a_different_connected_device() {
+        for policy in [ConnectPolicy::RejectIfConnected, ConnectPolicy::DisconnectExisting] {
+            assert!(!policy.must_disconnect(None, &"a").unwrap());
+            assert!(!policy.must_disconnect(Some(&"a"), &"a").unwrap());
+        }
+
+        assert!(ConnectPolicy::DisconnectExisting.must_disconnect(Some(&"a"), &"b").unwrap());
+        assert!(matches!(
+            ConnectPolicy::RejectIfConnected.must_disconnect(Some(&"a"), &"b"),
+            Err(CryptoNodeError::ResourceBusy(_))
+        ));
+    }
+
+    #[test]
+    fn consecutive_errors_trigger_one_reset_at_the_threshold() {
+        let streak = ErrorStreak { threshold: Some(3), ..ErrorStreak::default() };
+
+        assert_eq!(streak.record(true), None);
+        assert_eq!(streak.record(false), None);
+        assert_eq!(streak.record(true), None);
+        assert_eq!(streak.record(true), None);
+        assert_eq!(streak.record(true), Some(3));
+        // Errors racing the reset don't start another
+        assert_eq!(streak.record(true), None);
+
+        streak.finish_reset();
+        assert_eq!(streak.record(true), None);
+
+        let disabled = ErrorStreak::default();
+        assert!((0..10).all(|_| disabled.record(true).is_none()));
+    }
+
+    #[test]
+    fn device_commands_parse_from_tagged_json() {
+        let wallet_id = Uuid::new_v4();
+        let data = format!(r#"{{"command":"GetBalance","wallet_id":"{}"}}"#, wallet_id);
+        assert_eq!(DeviceCommand::parse(data.as_bytes()).unwrap(), DeviceCommand::GetBalance { wallet_id });
+
+        assert!(matches!(DeviceCommand::parse(br#"{"command":"Reboot"}"#), Err(CryptoNodeError::InvalidInput(_))));
+        assert!(matches!(DeviceCommand::parse(b"not json"), Err(CryptoNodeError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn pruning_policies_shape_the_discovery_cache() {
+        let cache = || HashMap::from([
+            ("fresh".to_string(), discovered("fresh", 1, None)),
+            ("old".to_string(), discovered("old", 600, None)),
+        ]);
+
+        let mut marked = cache();
+        prune_discoveries(&mut marked, DiscoveryPruning::MarkStale);
+        assert_eq!(marked.len(), 2);
+        assert!(marked.values().all(|d| d.stale));
+
+        let mut cleared = cache();
+        prune_discoveries(&mut cleared, DiscoveryPruning::Clear);
+        assert!(cleared.is_empty());
+
+        let mut aged = cache();
+        prune_discoveries(&mut aged, DiscoveryPruning::AgeOut(Duration::from_secs(60)));
+        assert_eq!(aged.keys().collect::<Vec<_>>(), vec!["fresh"]);
+        assert!(aged["fresh"].stale);
+    }
+}
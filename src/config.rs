@@ -1,25 +1,158 @@
 use crate::{
     Result,
+    bandwidth::DEFAULT_MIN_MEASUREMENT_INTERVAL,
     error::CryptoNodeError,
-    types::DeviceConfig,
+    storage,
+    types::{AutoBackupConfig, CurrencyType, DeviceConfig},
 };
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use std::fs;
 use std::path::{Path, PathBuf};
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, watch};
 use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+use uuid::Uuid;
 
 const CONFIG_FILE: &str = "config.json";
 
+/// How `import_config_with_policy` treats the imported config's device id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeviceIdPolicy {
+    /// Keep this device's current id
+    #[default]
+    Preserve,
+    /// Assign a freshly generated id
+    Regenerate,
+    /// Take over the imported id
+    Adopt,
+}
+
+/// Builds a `DeviceConfig`, validating it on `build`
+///
+/// Starts from `DeviceConfig::default()`, so an untouched builder yields the
+/// default config.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceConfigBuilder {
+    config: DeviceConfig,
+}
+
+impl DeviceConfigBuilder {
+    /// Create a builder seeded with the default config
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn device_id(mut self, device_id: Uuid) -> Self {
+        self.config.device_id = device_id;
+        self
+    }
+
+    pub fn device_name(mut self, name: impl Into<String>) -> Self {
+        self.config.device_name = name.into();
+        self
+    }
+
+    pub fn bluetooth_enabled(mut self, enabled: bool) -> Self {
+        self.config.bluetooth_enabled = enabled;
+        self
+    }
+
+    pub fn bluetooth_name(mut self, name: impl Into<String>) -> Self {
+        self.config.bluetooth_name = name.into();
+        self
+    }
+
+    pub fn min_bandwidth(mut self, min_bandwidth: u64) -> Self {
+        self.config.min_bandwidth = min_bandwidth;
+        self
+    }
+
+    pub fn max_bandwidth(mut self, max_bandwidth: u64) -> Self {
+        self.config.max_bandwidth = max_bandwidth;
+        self
+    }
+
+    pub fn reward_rate(mut self, rate: f64) -> Self {
+        self.config.reward_rate = rate;
+        self
+    }
+
+    pub fn min_reward_rate(mut self, rate: f64) -> Self {
+        self.config.min_reward_rate = rate;
+        self
+    }
+
+    pub fn measurement_interval(mut self, secs: u64) -> Self {
+        self.config.measurement_interval = secs;
+        self
+    }
+
+    pub fn supported_currencies(mut self, currencies: Vec<CurrencyType>) -> Self {
+        self.config.supported_currencies = currencies;
+        self
+    }
+
+    pub fn auto_update_enabled(mut self, enabled: bool) -> Self {
+        self.config.auto_update_enabled = enabled;
+        self
+    }
+
+    pub fn update_check_interval(mut self, secs: u64) -> Self {
+        self.config.update_check_interval = secs;
+        self
+    }
+
+    pub fn auto_backup(mut self, auto_backup: AutoBackupConfig) -> Self {
+        self.config.auto_backup = auto_backup;
+        self
+    }
+
+    pub fn safe_mode(mut self, enabled: bool) -> Self {
+        self.config.safe_mode = enabled;
+        self
+    }
+
+    pub fn startup_selftest(mut self, enabled: bool) -> Self {
+        self.config.startup_selftest = enabled;
+        self
+    }
+
+    /// Validate and return the config
+    ///
+    /// Runs the same checks as `ConfigManager::validate_config`.
+    pub fn build(self) -> Result<DeviceConfig> {
+        ConfigManager::validate(&self.config)?;
+        Ok(self.config)
+    }
+}
+
 /// Manages application configuration
 pub struct ConfigManager {
     config: Arc<RwLock<DeviceConfig>>,
     config_path: PathBuf,
+    lenient: bool,
+    watch_sender: watch::Sender<DeviceConfig>,
 }
 
 impl ConfigManager {
     /// Create a new configuration manager
+    ///
+    /// Unknown fields in the config file are rejected.
     pub async fn new() -> Result<Self> {
+        Self::with_lenient_parsing(false).await
+    }
+
+    /// Create a configuration manager that drops unknown config fields
+    ///
+    /// Intended for upgrades where an older or newer config file may carry
+    /// keys this version doesn't know about. Dropped fields are logged.
+    pub async fn new_lenient() -> Result<Self> {
+        Self::with_lenient_parsing(true).await
+    }
+
+    async fn with_lenient_parsing(lenient: bool) -> Result<Self> {
         let config_dir = dirs::config_dir()
             .ok_or_else(|| CryptoNodeError::Config("Could not determine config directory".to_string()))?
             .join("cryptonode");
@@ -27,28 +160,53 @@ impl ConfigManager {
         fs::create_dir_all(&config_dir)
             .map_err(|e| CryptoNodeError::Config(format!("Failed to create config directory: {}", e)))?;
 
-        let config_path = config_dir.join(CONFIG_FILE);
+        Self::open(config_dir.join(CONFIG_FILE), lenient)
+    }
+
+    /// Load the config at `config_path`, writing the defaults if it doesn't exist
+    fn open(config_path: PathBuf, lenient: bool) -> Result<Self> {
         let config = if config_path.exists() {
-            Self::load_config(&config_path)?
+            Self::load_config(&config_path, lenient)?
         } else {
             let default_config = DeviceConfig::default();
             Self::save_config(&config_path, &default_config)?;
             default_config
         };
 
+        let (watch_sender, _) = watch::channel(config.clone());
+
         Ok(Self {
             config: Arc::new(RwLock::new(config)),
             config_path,
+            lenient,
+            watch_sender,
         })
     }
 
     /// Load configuration from file
-    fn load_config(path: &Path) -> Result<DeviceConfig> {
+    fn load_config(path: &Path, lenient: bool) -> Result<DeviceConfig> {
         let config_str = fs::read_to_string(path)
             .map_err(|e| CryptoNodeError::Config(format!("Failed to read config file: {}", e)))?;
 
-        serde_json::from_str(&config_str)
-            .map_err(|e| CryptoNodeError::Config(format!("Failed to parse config file: {}", e)))
+        Self::parse_config(&config_str, lenient)
+    }
+
+    /// Parse configuration, optionally dropping unknown fields
+    fn parse_config(config_str: &str, lenient: bool) -> Result<DeviceConfig> {
+        if !lenient {
+            return serde_json::from_str(config_str)
+                .map_err(|e| CryptoNodeError::Config(format!("Failed to parse config file: {}", e)));
+        }
+
+        let mut value: serde_json::Value = serde_json::from_str(config_str)
+            .map_err(|e| CryptoNodeError::Config(format!("Failed to parse config file: {}", e)))?;
+
+        // serde doesn't say where an unknown field is, so nested sections are
+        // cleaned on their own before the top level
+        if let Some(auto_backup) = value.get_mut("auto_backup") {
+            parse_lenient::<AutoBackupConfig>(auto_backup, "auto_backup.")?;
+        }
+        parse_lenient(&mut value, "")
     }
 
     /// Save configuration to file
@@ -56,10 +214,7 @@ impl ConfigManager {
         let config_str = serde_json::to_string_pretty(config)
             .map_err(|e| CryptoNodeError::Config(format!("Failed to serialize config: {}", e)))?;
 
-        fs::write(path, config_str)
-            .map_err(|e| CryptoNodeError::Config(format!("Failed to write config file: {}", e)))?;
-
-        Ok(())
+        storage::write_atomic(path, config_str.as_bytes())
     }
 
     /// Get current configuration
@@ -68,22 +223,38 @@ impl ConfigManager {
         Ok(config.clone())
     }
 
+    /// Watch for configuration changes
+    ///
+    /// The receiver always holds the latest committed config.
+    pub fn watch(&self) -> watch::Receiver<DeviceConfig> {
+        self.watch_sender.subscribe()
+    }
+
     /// Update configuration
+    ///
+    /// An invalid config is rejected and the current one kept.
     pub async fn update_config(&self, new_config: DeviceConfig) -> Result<()> {
+        Self::validate(&new_config)?;
+
+        // Hold the write lock across the file write so writers are serialized
+        let mut config = self.config.write().await;
+
         // Save to file first to ensure persistence
         Self::save_config(&self.config_path, &new_config)?;
 
         // Update in-memory config
-        let mut config = self.config.write().await;
         *config = new_config;
+        self.watch_sender.send_replace(config.clone());
 
         Ok(())
     }
 
     /// Update specific configuration field
+    ///
+    /// The resulting config must pass validation.
     pub async fn update_field<T: Serialize>(&self, field: &str, value: T) -> Result<()> {
         let mut config = self.config.write().await;
-        let config_value = serde_json::to_value(&config)
+        let config_value = serde_json::to_value(&*config)
             .map_err(|e| CryptoNodeError::Config(format!("Failed to serialize config: {}", e)))?;
 
         let mut config_map = config_value.as_object()
@@ -95,10 +266,38 @@ impl ConfigManager {
 
         config_map.insert(field.to_string(), value);
 
-        *config = serde_json::from_value(serde_json::Value::Object(config_map))
+        let new_config: DeviceConfig = serde_json::from_value(serde_json::Value::Object(config_map))
             .map_err(|e| CryptoNodeError::Config(format!("Failed to update config: {}", e)))?;
+        Self::validate(&new_config)?;
 
-        Self::save_config(&self.config_path, &config)?;
+        // The write lock is held from the read above until the swap below
+        Self::save_config(&self.config_path, &new_config)?;
+        *config = new_config;
+        self.watch_sender.send_replace(config.clone());
+
+        Ok(())
+    }
+
+    /// Apply an RFC 6902 JSON Patch to the configuration
+    ///
+    /// The patched config must deserialize and pass validation before it is
+    /// persisted; otherwise the current config is left untouched.
+    pub async fn apply_patch(&self, patch: json_patch::Patch) -> Result<()> {
+        let mut config = self.config.write().await;
+        let mut config_value = serde_json::to_value(&*config)
+            .map_err(|e| CryptoNodeError::Config(format!("Failed to serialize config: {}", e)))?;
+
+        json_patch::patch(&mut config_value, &patch)
+            .map_err(|e| CryptoNodeError::Config(format!("Failed to apply patch: {}", e)))?;
+
+        let new_config: DeviceConfig = serde_json::from_value(config_value)
+            .map_err(|e| CryptoNodeError::Config(format!("Patched config is invalid: {}", e)))?;
+        Self::validate(&new_config)?;
+
+        // Only swap in memory once the file is safely written
+        Self::save_config(&self.config_path, &new_config)?;
+        *config = new_config;
+        self.watch_sender.send_replace(config.clone());
 
         Ok(())
     }
@@ -117,17 +316,19 @@ impl ConfigManager {
     /// Validate configuration
     pub async fn validate_config(&self) -> Result<()> {
         let config = self.config.read().await;
-        
+        Self::validate(&config)
+    }
+
+    /// Validate a configuration value
+    pub(crate) fn validate(config: &DeviceConfig) -> Result<()> {
         // Validate device name
         if config.device_name.is_empty() {
             return Err(CryptoNodeError::Config("Device name cannot be empty".to_string()));
         }
 
         // Validate Bluetooth settings
-        if config.bluetooth_enabled {
-            if config.bluetooth_name.is_empty() {
-                return Err(CryptoNodeError::Config("Bluetooth name cannot be empty when enabled".to_string()));
-            }
+        if config.bluetooth_enabled && config.bluetooth_name.is_empty() {
+            return Err(CryptoNodeError::Config("Bluetooth name cannot be empty when enabled".to_string()));
         }
 
         // Validate bandwidth settings
@@ -139,6 +340,13 @@ impl ConfigManager {
             return Err(CryptoNodeError::Config("Reward rate cannot be negative".to_string()));
         }
 
+        // Same floor `BandwidthManager` enforces by default
+        if Duration::from_secs(config.measurement_interval) < DEFAULT_MIN_MEASUREMENT_INTERVAL {
+            return Err(CryptoNodeError::Config(format!(
+                "Measurement interval must be at least {:?}", DEFAULT_MIN_MEASUREMENT_INTERVAL
+            )));
+        }
+
         // Validate update settings
         if config.auto_update_enabled && config.update_check_interval == 0 {
             return Err(CryptoNodeError::Config("Update check interval cannot be zero when auto-update is enabled".to_string()));
@@ -153,9 +361,309 @@ impl ConfigManager {
         Self::save_config(path, &config)
     }
 
-    /// Import configuration from file
+    /// Import configuration from file, keeping this device's id
     pub async fn import_config(&self, path: &Path) -> Result<()> {
-        let new_config = Self::load_config(path)?;
-        self.update_config(new_config).await
+        self.import_config_with_policy(path, DeviceIdPolicy::default()).await
+    }
+
+    /// Import configuration from file
+    ///
+    /// The imported config must pass validation. The imported `device_id` is
+    /// only used with `DeviceIdPolicy::Adopt`, so importing another device's
+    /// config does not clone its identity.
+    pub async fn import_config_with_policy(&self, path: &Path, policy: DeviceIdPolicy) -> Result<()> {
+        let mut new_config = Self::load_config(path, self.lenient)?;
+        let mut config = self.config.write().await;
+
+        match policy {
+            DeviceIdPolicy::Preserve => new_config.device_id = config.device_id,
+            DeviceIdPolicy::Regenerate => new_config.device_id = Uuid::new_v4(),
+            DeviceIdPolicy::Adopt => {
+                if new_config.device_id != config.device_id {
+                    warn!("Adopting imported device id {}", new_config.device_id);
+                }
+            }
+        }
+        Self::validate(&new_config)?;
+
+        Self::save_config(&self.config_path, &new_config)?;
+        *config = new_config;
+        self.watch_sender.send_replace(config.clone());
+
+        Ok(())
+    }
+}
+
+/// Parse `value`, removing its unknown top-level keys until it parses
+///
+/// `prefix` locates `value` within the config file in warnings.
+fn parse_lenient<T: DeserializeOwned>(value: &mut serde_json::Value, prefix: &str) -> Result<T> {
+    loop {
+        let err = match serde_json::from_value::<T>(value.clone()) {
+            Ok(parsed) => return Ok(parsed),
+            Err(e) => e,
+        };
+
+        // Strip the offending key and retry; anything else is a real error
+        let unknown = unknown_field_name(&err.to_string());
+        let removed = match (unknown, value.as_object_mut()) {
+            (Some(field), Some(map)) => map.remove(&field).map(|_| field),
+            _ => None,
+        };
+        match removed {
+            Some(field) => warn!("Ignoring unknown config field `{}{}`", prefix, field),
+            None => return Err(CryptoNodeError::Config(format!("Failed to parse config file: {}", err))),
+        }
+    }
+}
+
+/// Extract the field name from a serde "unknown field" error message
+fn unknown_field_name(message: &str) -> Option<String> {
+    let rest = message.strip_prefix("unknown field `")?;
+    let end = rest.find('`')?;
+    Some(rest[..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_manager() -> ConfigManager {
+        let dir = std::env::temp_dir().join(format!("cryptonode-config-{}", Uuid::new_v4()));
+        ConfigManager::open(dir.join(CONFIG_FILE), false).unwrap()
+    }
+
+    fn invalid_config() -> DeviceConfig {
+        DeviceConfig {
+            device_name: String::new(),
+            ..DeviceConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn update_config_rejects_invalid_config() {
+        let manager = temp_manager();
+        let before = manager.get_config().await.unwrap();
+
+        assert!(manager.update_config(invalid_config()).await.is_err());
+        assert_eq!(manager.get_config().await.unwrap().device_name, before.device_name);
+    }
+
+    #[tokio::test]
+    async fn update_field_rejects_invalid_value() {
+        let manager = temp_manager();
+        assert!(manager.update_field("min_bandwidth", 0u64).await.is_err());
+        assert!(manager.update_field("min_bandwidth", 2048u64).await.is_ok());
+        assert_eq!(manager.get_config().await.unwrap().min_bandwidth, 2048);
+    }
+
+    #[tokio::test]
+    async fn import_config_rejects_invalid_file() {
+        let manager = temp_manager();
+        let path = manager.get_config_path().with_file_name("invalid.json");
+        ConfigManager::save_config(&path, &invalid_config()).unwrap();
+
+        assert!(manager.import_config(&path).await.is_err());
+        assert!(!manager.get_config().await.unwrap().device_name.is_empty());
+    }
+
+    #[tokio::test]
+    async fn reset_config_stores_valid_defaults() {
+        let manager = temp_manager();
+        manager.update_field("device_name", "renamed").await.unwrap();
+        manager.reset_config().await.unwrap();
+        assert!(manager.validate_config().await.is_ok());
     }
-} 
\ No newline at end of file
+
+    #[tokio::test]
+    async fn import_config_keeps_device_id_by_default() {
+        let manager = temp_manager();
+        let own_id = manager.get_config().await.unwrap().device_id;
+        let path = manager.get_config_path().with_file_name("other.json");
+        ConfigManager::save_config(&path, &DeviceConfig::default()).unwrap();
+
+        manager.import_config(&path).await.unwrap();
+        assert_eq!(manager.get_config().await.unwrap().device_id, own_id);
+    }
+
+    #[tokio::test]
+    async fn import_config_with_policy_applies_policy() {
+        let manager = temp_manager();
+        let own_id = manager.get_config().await.unwrap().device_id;
+        let imported = DeviceConfig::default();
+        let path = manager.get_config_path().with_file_name("other.json");
+        ConfigManager::save_config(&path, &imported).unwrap();
+
+        manager.import_config_with_policy(&path, DeviceIdPolicy::Adopt).await.unwrap();
+        assert_eq!(manager.get_config().await.unwrap().device_id, imported.device_id);
+
+        manager.import_config_with_policy(&path, DeviceIdPolicy::Regenerate).await.unwrap();
+        let regenerated = manager.get_config().await.unwrap().device_id;
+        assert_ne!(regenerated, imported.device_id);
+        assert_ne!(regenerated, own_id);
+    }
+
+    /// The default config serialized with an extra top-level field
+    fn config_with_typo() -> String {
+        let mut value = serde_json::to_value(DeviceConfig::default()).unwrap();
+        value.as_object_mut().unwrap().insert("device_nmae".to_string(), "typo".into());
+        value.to_string()
+    }
+
+    #[test]
+    fn strict_parsing_rejects_unknown_fields() {
+        let err = ConfigManager::parse_config(&config_with_typo(), false).unwrap_err();
+        assert!(err.to_string().contains("device_nmae"));
+    }
+
+    #[test]
+    fn lenient_parsing_drops_unknown_fields() {
+        let config = ConfigManager::parse_config(&config_with_typo(), true).unwrap();
+        assert_eq!(config.device_name, DeviceConfig::default().device_name);
+    }
+
+    #[test]
+    fn lenient_parsing_still_rejects_bad_values() {
+        let mut value = serde_json::to_value(DeviceConfig::default()).unwrap();
+        value["min_bandwidth"] = "lots".into();
+        assert!(ConfigManager::parse_config(&value.to_string(), true).is_err());
+    }
+
+    fn patch(operations: serde_json::Value) -> json_patch::Patch {
+        serde_json::from_value(operations).unwrap()
+    }
+
+    #[tokio::test]
+    async fn patch_updates_and_persists_fields() {
+        let manager = temp_manager();
+        manager.apply_patch(patch(serde_json::json!([
+            { "op": "replace", "path": "/device_name", "value": "patched" },
+            { "op": "replace", "path": "/min_bandwidth", "value": 4096 },
+        ]))).await.unwrap();
+
+        let config = manager.get_config().await.unwrap();
+        assert_eq!(config.device_name, "patched");
+        assert_eq!(config.min_bandwidth, 4096);
+        let saved = ConfigManager::load_config(manager.get_config_path(), false).unwrap();
+        assert_eq!(saved.device_name, "patched");
+    }
+
+    #[tokio::test]
+    async fn failed_patch_leaves_config_unchanged() {
+        let manager = temp_manager();
+        let before = manager.get_config().await.unwrap().device_name;
+
+        // Fails validation
+        assert!(manager.apply_patch(patch(serde_json::json!([
+            { "op": "replace", "path": "/device_name", "value": "" },
+        ]))).await.is_err());
+        // A failing test op aborts the whole patch
+        assert!(manager.apply_patch(patch(serde_json::json!([
+            { "op": "replace", "path": "/device_name", "value": "patched" },
+            { "op": "test", "path": "/min_bandwidth", "value": -1 },
+        ]))).await.is_err());
+        // Unknown fields are rejected like in the file
+        assert!(manager.apply_patch(patch(serde_json::json!([
+            { "op": "add", "path": "/device_nmae", "value": "typo" },
+        ]))).await.is_err());
+
+        assert_eq!(manager.get_config().await.unwrap().device_name, before);
+    }
+
+    #[tokio::test]
+    async fn concurrent_field_updates_are_all_kept() {
+        let manager = Arc::new(temp_manager());
+        let names = {
+            let manager = manager.clone();
+            tokio::spawn(async move {
+                for i in 0..20 {
+                    manager.update_field("device_name", format!("node-{}", i)).await.unwrap();
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
+        for bandwidth in 1..=20u64 {
+            manager.update_field("min_bandwidth", bandwidth * 1024).await.unwrap();
+            tokio::task::yield_now().await;
+        }
+        names.await.unwrap();
+
+        let in_memory = manager.get_config().await.unwrap();
+        let on_disk = ConfigManager::load_config(manager.get_config_path(), false).unwrap();
+        assert_eq!(in_memory.device_name, "node-19");
+        assert_eq!(in_memory.min_bandwidth, 20 * 1024);
+        assert_eq!(on_disk.device_name, in_memory.device_name);
+        assert_eq!(on_disk.min_bandwidth, in_memory.min_bandwidth);
+    }
+
+    #[test]
+    fn lenient_parsing_drops_nested_unknown_fields_in_place() {
+        let mut value = serde_json::to_value(DeviceConfig { safe_mode: true, ..DeviceConfig::default() }).unwrap();
+        value["auto_backup"]["enabled"] = true.into();
+        value["auto_backup"]["safe_mode"] = false.into();
+
+        assert!(ConfigManager::parse_config(&value.to_string(), false).is_err());
+        let config = ConfigManager::parse_config(&value.to_string(), true).unwrap();
+        assert!(config.safe_mode); // the top-level setting with the same name survives
+        assert!(config.auto_backup.enabled);
+    }
+
+    #[test]
+    fn builder_applies_setters_and_validates() {
+        let id = Uuid::new_v4();
+        let config = DeviceConfigBuilder::new()
+            .device_id(id)
+            .device_name("gateway")
+            .min_bandwidth(4096)
+            .safe_mode(true)
+            .build()
+            .unwrap();
+        assert_eq!((config.device_id, config.device_name.as_str(), config.min_bandwidth), (id, "gateway", 4096));
+        assert!(config.safe_mode);
+
+        assert!(matches!(DeviceConfigBuilder::new().device_name("").build(), Err(CryptoNodeError::Config(_))));
+        assert!(DeviceConfigBuilder::new().bluetooth_enabled(true).bluetooth_name("").build().is_err());
+        assert!(DeviceConfigBuilder::new().bluetooth_enabled(false).bluetooth_name("").build().is_ok());
+        assert!(DeviceConfigBuilder::new().reward_rate(-1.0).build().is_err());
+    }
+
+    #[test]
+    fn builder_enforces_measurement_interval_floor() {
+        let floor = DEFAULT_MIN_MEASUREMENT_INTERVAL.as_secs();
+        assert!(DeviceConfigBuilder::new().measurement_interval(floor - 1).build().is_err());
+        assert!(DeviceConfigBuilder::new().measurement_interval(floor).build().is_ok());
+    }
+
+    #[test]
+    fn configs_without_the_new_fields_load_with_defaults() {
+        let mut value = serde_json::to_value(DeviceConfig::default()).unwrap();
+        let fields = value.as_object_mut().unwrap();
+        for key in ["device_name", "bluetooth_enabled", "min_bandwidth", "reward_rate", "update_check_interval"] {
+            fields.remove(key);
+        }
+        let auto_update = fields.remove("auto_update_enabled").unwrap();
+        fields.insert("auto_update".to_string(), auto_update);
+
+        let config = ConfigManager::parse_config(&value.to_string(), false).unwrap();
+        assert_eq!(config.device_name, "CryptoNode");
+        assert!(config.bluetooth_enabled && config.auto_update_enabled);
+        assert_eq!(config.min_bandwidth, 1024 * 1024);
+        assert_eq!(config.update_check_interval, 24 * 60 * 60);
+    }
+
+    #[test]
+    fn validate_checks_bandwidth_and_update_fields() {
+        let zero_floor = DeviceConfig { min_bandwidth: 0, ..DeviceConfig::default() };
+        assert!(matches!(ConfigManager::validate(&zero_floor), Err(CryptoNodeError::Config(_))));
+
+        let never_checks = DeviceConfig { update_check_interval: 0, ..DeviceConfig::default() };
+        assert!(ConfigManager::validate(&never_checks).is_err());
+        let updates_off = DeviceConfig { auto_update_enabled: false, ..never_checks };
+        assert!(ConfigManager::validate(&updates_off).is_ok());
+    }
+
+    #[test]
+    fn default_config_passes_validation() {
+        assert!(ConfigManager::validate(&DeviceConfig::default()).is_ok());
+    }
+}
@@ -0,0 +1,339 @@
+use crate::{Result, error::CryptoNodeError, types::CurrencyType};
+use aes_gcm::{Aes256Gcm, Nonce, aead::{Aead, KeyInit}};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::num::NonZeroU32;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const PBKDF2_ITERATIONS: u32 = 100_000;
+
+/// Bytes drawn for the entropy health check
+const ENTROPY_SAMPLE_LEN: usize = 64;
+/// Health check attempts before the source is declared degraded
+const ENTROPY_CHECK_ATTEMPTS: usize = 3;
+
+/// Prefix prepended to arbitrary messages before signing
+///
+/// Keeps a message signature from ever being valid over a transaction's
+/// signing payload, which carries no prefix.
+const MESSAGE_DOMAIN: &[u8] = b"CryptoNode Signed Message:\n";
+
+/// Prefix hashed into derived receive addresses
+const RECEIVE_ADDRESS_DOMAIN: &[u8] = b"CryptoNode Receive Address:\n";
+
+/// Source of random bytes for key generation
+pub trait EntropySource: Send + Sync {
+    fn fill_bytes(&self, dest: &mut [u8]) -> Result<()>;
+}
+
+impl EntropySource for SystemRandom {
+    fn fill_bytes(&self, dest: &mut [u8]) -> Result<()> {
+        SecureRandom::fill(self, dest)
+            .map_err(|e| CryptoNodeError::CryptoOperation(e.to_string()))
+    }
+}
+
+/// Check that an entropy source looks healthy before trusting it with keys
+///
+/// Draws a sample and rejects it if every byte is equal or the share of set
+/// bits is implausible (monobit test, roughly 4 standard deviations). A few
+/// attempts are allowed since a good source can fail by chance.
+pub fn check_entropy(source: &dyn EntropySource) -> Result<()> {
+    for _ in 0..ENTROPY_CHECK_ATTEMPTS {
+        let mut sample = [0u8; ENTROPY_SAMPLE_LEN];
+        source.fill_bytes(&mut sample)?;
+        if sample_looks_random(&sample) {
+            return Ok(());
+        }
+    }
+    Err(CryptoNodeError::CryptoOperation("Entropy source appears degraded".to_string()))
+}
+
+fn sample_looks_random(sample: &[u8]) -> bool {
+    if sample.iter().all(|&b| b == sample[0]) {
+        return false;
+    }
+    let bits = (sample.len() * 8) as f64;
+    let ones = sample.iter().map(|b| b.count_ones()).sum::<u32>() as f64;
+    let tolerance = 4.0 * (bits / 4.0).sqrt();
+    (ones - bits / 2.0).abs() <= tolerance
+}
+
+/// Reconstruct an ed25519 signing key from raw secret key bytes
+pub fn signing_key_from_bytes(secret_key: &[u8]) -> Result<SigningKey> {
+    let bytes: [u8; 32] = secret_key.try_into()
+        .map_err(|_| CryptoNodeError::CryptoOperation("Secret key must be 32 bytes".to_string()))?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+/// Validate an imported secret key and return the signing key it encodes
+///
+/// Rejects keys of the wrong length, all-zero keys, and keys whose public
+/// half is a weak (small-order) point.
+pub fn validate_secret_key(secret_key: &[u8]) -> Result<SigningKey> {
+    if secret_key.len() != 32 {
+        return Err(CryptoNodeError::InvalidInput("Secret key must be 32 bytes".to_string()));
+    }
+    if secret_key.iter().all(|&b| b == 0) {
+        return Err(CryptoNodeError::InvalidInput("Secret key is all zeros".to_string()));
+    }
+    let signing_key = signing_key_from_bytes(secret_key)
+        .map_err(|e| CryptoNodeError::InvalidInput(e.to_string()))?;
+    if signing_key.verifying_key().is_weak() {
+        return Err(CryptoNodeError::InvalidInput("Secret key yields a weak public key".to_string()));
+    }
+    Ok(signing_key)
+}
+
+/// Parse an ed25519 verifying key from raw public key bytes
+pub fn verifying_key_from_bytes(public_key: &[u8]) -> Result<VerifyingKey> {
+    let bytes: [u8; 32] = public_key.try_into()
+        .map_err(|_| CryptoNodeError::CryptoOperation("Public key must be 32 bytes".to_string()))?;
+    VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| CryptoNodeError::CryptoOperation(e.to_string()))
+}
+
+/// Sign a payload with the given secret key
+pub fn sign(secret_key: &[u8], payload: &[u8]) -> Result<Vec<u8>> {
+    let signing_key = signing_key_from_bytes(secret_key)?;
+    Ok(signing_key.sign(payload).to_bytes().to_vec())
+}
+
+/// Verify a signature over a payload
+///
+/// Returns `Ok(false)` when the signature does not match, and an error when
+/// the key or signature bytes are malformed.
+pub fn verify(public_key: &[u8], payload: &[u8], signature: &[u8]) -> Result<bool> {
+    let verifying_key = verifying_key_from_bytes(public_key)?;
+    let signature = Signature::from_slice(signature)
+        .map_err(|e| CryptoNodeError::CryptoOperation(e.to_string()))?;
+    Ok(verifying_key.verify(payload, &signature).is_ok())
+}
+
+/// Signature scheme a wallet's key pair belongs to
+///
+/// Wallets store this so signing and verification use the right curve.
+/// Public keys are self-describing by length (32 bytes for ed25519, 33 for a
+/// compressed secp256k1 point), which lets addresses be verified without it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum SigningAlgorithm {
+    #[default]
+    Ed25519,
+    Secp256k1,
+}
+
+impl SigningAlgorithm {
+    /// Natural algorithm for a currency's chain
+    ///
+    /// Tokens are ERC-20 contracts and follow Ethereum.
+    pub fn for_currency(currency: &CurrencyType) -> Self {
+        match currency {
+            CurrencyType::Bitcoin | CurrencyType::Ethereum | CurrencyType::Token { .. } => SigningAlgorithm::Secp256k1,
+            CurrencyType::Unknown(_) => SigningAlgorithm::Ed25519,
+        }
+    }
+
+    /// Infer the algorithm from a public key's length
+    pub fn from_public_key(public_key: &[u8]) -> Result<Self> {
+        match public_key.len() {
+            32 => Ok(SigningAlgorithm::Ed25519),
+            33 => Ok(SigningAlgorithm::Secp256k1),
+            len => Err(CryptoNodeError::CryptoOperation(format!("Unrecognized public key length {}", len))),
+        }
+    }
+
+    /// Validate a secret key for this algorithm
+    pub fn validate_secret_key(&self, secret_key: &[u8]) -> Result<()> {
+        match self {
+            SigningAlgorithm::Ed25519 => validate_secret_key(secret_key).map(|_| ()),
+            SigningAlgorithm::Secp256k1 => secp256k1_signing_key(secret_key)
+                .map(|_| ())
+                .map_err(|e| CryptoNodeError::InvalidInput(e.to_string())),
+        }
+    }
+
+    /// Derive the public key for a secret key
+    ///
+    /// secp256k1 public keys are SEC1-compressed.
+    pub fn public_key(&self, secret_key: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            SigningAlgorithm::Ed25519 => Ok(signing_key_from_bytes(secret_key)?.verifying_key().as_bytes().to_vec()),
+            SigningAlgorithm::Secp256k1 => Ok(secp256k1_signing_key(secret_key)?
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec()),
+        }
+    }
+
+    /// Sign a payload with a secret key of this algorithm
+    pub fn sign(&self, secret_key: &[u8], payload: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            SigningAlgorithm::Ed25519 => sign(secret_key, payload),
+            SigningAlgorithm::Secp256k1 => {
+                let signature: k256::ecdsa::Signature = secp256k1_signing_key(secret_key)?.sign(payload);
+                Ok(signature.to_bytes().to_vec())
+            }
+        }
+    }
+
+    /// Verify a signature over a payload with a public key of this algorithm
+    ///
+    /// Returns `Ok(false)` when the signature does not match, and an error
+    /// when the key or signature bytes are malformed.
+    pub fn verify(&self, public_key: &[u8], payload: &[u8], signature: &[u8]) -> Result<bool> {
+        match self {
+            SigningAlgorithm::Ed25519 => verify(public_key, payload, signature),
+            SigningAlgorithm::Secp256k1 => {
+                let verifying_key = k256::ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+                    .map_err(|e| CryptoNodeError::CryptoOperation(e.to_string()))?;
+                let signature = k256::ecdsa::Signature::from_slice(signature)
+                    .map_err(|e| CryptoNodeError::CryptoOperation(e.to_string()))?;
+                Ok(verifying_key.verify(payload, &signature).is_ok())
+            }
+        }
+    }
+}
+
+/// Reconstruct a secp256k1 signing key from raw secret key bytes
+fn secp256k1_signing_key(secret_key: &[u8]) -> Result<k256::ecdsa::SigningKey> {
+    // `from_slice` left-pads short scalars, which would accept truncated keys
+    if secret_key.len() != 32 {
+        return Err(CryptoNodeError::CryptoOperation("Secret key must be 32 bytes".to_string()));
+    }
+    k256::ecdsa::SigningKey::from_slice(secret_key)
+        .map_err(|_| CryptoNodeError::CryptoOperation("Invalid secp256k1 secret key".to_string()))
+}
+
+/// Verify a signature with the algorithm implied by the public key
+pub fn verify_any(public_key: &[u8], payload: &[u8], signature: &[u8]) -> Result<bool> {
+    SigningAlgorithm::from_public_key(public_key)?.verify(public_key, payload, signature)
+}
+
+/// Derive the `index`th receive address of a public key
+///
+/// Deterministic, so the same key and index always give the same address.
+pub fn derive_receive_address(public_key: &[u8], index: u32) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(RECEIVE_ADDRESS_DOMAIN);
+    hasher.update(public_key);
+    hasher.update(index.to_be_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Bytes actually signed for an arbitrary message
+pub fn message_payload(message: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(MESSAGE_DOMAIN.len() + message.len());
+    payload.extend_from_slice(MESSAGE_DOMAIN);
+    payload.extend_from_slice(message);
+    payload
+}
+
+/// Derive an AES-256 key from a passphrase and salt
+fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    let iterations = NonZeroU32::new(PBKDF2_ITERATIONS).expect("iteration count is non-zero");
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key);
+    key
+}
+
+/// Encrypt data with a passphrase-derived AES-256-GCM key
+///
+/// Output layout is `salt || nonce || ciphertext`.
+pub fn encrypt_with_passphrase(passphrase: &str, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt)
+        .map_err(|e| CryptoNodeError::CryptoOperation(e.to_string()))?;
+    rng.fill(&mut nonce)
+        .map_err(|e| CryptoNodeError::CryptoOperation(e.to_string()))?;
+
+    let cipher = Aes256Gcm::new_from_slice(&derive_key(passphrase, &salt))
+        .map_err(|e| CryptoNodeError::CryptoOperation(e.to_string()))?;
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| CryptoNodeError::CryptoOperation("Encryption failed".to_string()))?;
+
+    let mut output = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+    output.extend_from_slice(&salt);
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(&ciphertext);
+    Ok(output)
+}
+
+/// Decrypt data produced by `encrypt_with_passphrase`
+pub fn decrypt_with_passphrase(passphrase: &str, data: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < SALT_LEN + NONCE_LEN {
+        return Err(CryptoNodeError::CryptoOperation("Encrypted data is truncated".to_string()));
+    }
+    let (salt, rest) = data.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let cipher = Aes256Gcm::new_from_slice(&derive_key(passphrase, salt))
+        .map_err(|e| CryptoNodeError::CryptoOperation(e.to_string()))?;
+    cipher.decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| CryptoNodeError::Security("Decryption failed: wrong passphrase or corrupted data".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_and_short_secret_keys_are_rejected() {
+        for algorithm in [SigningAlgorithm::Ed25519, SigningAlgorithm::Secp256k1] {
+            assert!(matches!(algorithm.validate_secret_key(&[0; 32]), Err(CryptoNodeError::InvalidInput(_))));
+            assert!(matches!(algorithm.validate_secret_key(&[7; 31]), Err(CryptoNodeError::InvalidInput(_))));
+            assert!(algorithm.validate_secret_key(&[7; 32]).is_ok());
+        }
+    }
+
+    #[test]
+    fn secp256k1_keys_beyond_the_curve_order_are_rejected() {
+        assert!(matches!(
+            SigningAlgorithm::Secp256k1.validate_secret_key(&[0xff; 32]),
+            Err(CryptoNodeError::InvalidInput(_))
+        ));
+    }
+
+    /// Fills every buffer with the same byte
+    struct StuckSource(u8);
+
+    impl EntropySource for StuckSource {
+        fn fill_bytes(&self, dest: &mut [u8]) -> Result<()> {
+            dest.fill(self.0);
+            Ok(())
+        }
+    }
+
+    /// Alternates 0xff and 0x7f, so bytes differ but most bits are set
+    struct BiasedSource;
+
+    impl EntropySource for BiasedSource {
+        fn fill_bytes(&self, dest: &mut [u8]) -> Result<()> {
+            for (i, byte) in dest.iter_mut().enumerate() {
+                *byte = if i % 2 == 0 { 0xff } else { 0x7f };
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn degraded_entropy_sources_fail_the_health_check() {
+        assert!(check_entropy(&SystemRandom::new()).is_ok());
+        assert!(matches!(check_entropy(&StuckSource(0)), Err(CryptoNodeError::CryptoOperation(_))));
+        assert!(matches!(check_entropy(&BiasedSource), Err(CryptoNodeError::CryptoOperation(_))));
+    }
+
+    #[test]
+    fn receive_addresses_are_deterministic_per_key_and_index() {
+        let key = [2u8; 33];
+        assert_eq!(derive_receive_address(&key, 0), derive_receive_address(&key, 0));
+        assert_ne!(derive_receive_address(&key, 0), derive_receive_address(&key, 1));
+        assert_ne!(derive_receive_address(&key, 0), derive_receive_address(&[3u8; 33], 0));
+    }
+}
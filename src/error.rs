@@ -1,4 +1,5 @@
 use thiserror::Error;
+use std::fmt;
 use std::io;
 
 #[derive(Error, Debug)]
@@ -37,11 +38,14 @@ pub enum CryptoNodeError {
     Device(String),
 
     #[error("Network error: {0}")]
-    Network(String),
+    Network(NetworkFailure),
 
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    #[error("Insufficient balance: {0}")]
+    InsufficientBalance(String),
+
     #[error("Not implemented: {0}")]
     NotImplemented(String),
 
@@ -62,4 +66,74 @@ pub enum CryptoNodeError {
 
     #[error("Unknown error: {0}")]
     Unknown(String),
-} 
\ No newline at end of file
+}
+
+impl CryptoNodeError {
+    /// Whether the failed operation may succeed if attempted again
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            CryptoNodeError::Network(failure) => failure.retryable,
+            CryptoNodeError::Io(_)
+            | CryptoNodeError::Bluetooth(_)
+            | CryptoNodeError::Timeout
+            | CryptoNodeError::ResourceBusy(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Details of a failed request to a network backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkFailure {
+    pub endpoint: String,
+    pub status: Option<u16>, // HTTP status, if a response was received
+    pub retryable: bool,
+    pub message: String,
+}
+
+impl NetworkFailure {
+    /// Describe a failure, inferring retryability from the status
+    ///
+    /// Failures without a response, timeouts, rate limiting and server
+    /// errors are retryable; other client errors are not.
+    pub fn new(endpoint: impl Into<String>, status: Option<u16>, message: impl Into<String>) -> Self {
+        let retryable = match status {
+            None => true,
+            Some(code) => code == 408 || code == 429 || code >= 500,
+        };
+        Self {
+            endpoint: endpoint.into(),
+            status,
+            retryable,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for NetworkFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(status) => write!(f, "{} returned {}: {}", self.endpoint, status, self.message),
+            None => write!(f, "{}: {}", self.endpoint, self.message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_failures_classify_retryability_by_status() {
+        let unreachable = NetworkFailure::new("https://node.example/submit", None, "connection refused");
+        assert!(unreachable.retryable);
+        assert_eq!(unreachable.to_string(), "https://node.example/submit: connection refused");
+
+        for status in [408, 429, 500, 503] {
+            assert!(CryptoNodeError::Network(NetworkFailure::new("/poll", Some(status), "")).is_retryable(), "{}", status);
+        }
+        let rejected = NetworkFailure::new("/submit", Some(400), "bad request");
+        assert_eq!(rejected.to_string(), "/submit returned 400: bad request");
+        assert!(!CryptoNodeError::Network(rejected).is_retryable());
+    }
+}
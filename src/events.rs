@@ -0,0 +1,160 @@
+use crate::{
+    bandwidth::{BandwidthManager, RewardEvent},
+    bluetooth::BluetoothEvent,
+    tasks,
+    wallet::{WalletEvent, WalletManager},
+};
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// An event from any of the node's managers, tagged by source
+#[derive(Debug, Clone)]
+pub enum NodeEvent {
+    Bluetooth(BluetoothEvent),
+    Wallet(WalletEvent),
+    Reward(RewardEvent),
+}
+
+/// Multiplexes Bluetooth, wallet and reward events into one stream
+///
+/// Every source is wired in by `for_node`, so none can be left out. Each is
+/// forwarded by its own task; the tasks stop when the bus is dropped.
+pub struct EventBus {
+    sender: broadcast::Sender<NodeEvent>,
+    forwarders: Vec<JoinHandle<()>>,
+}
+
+impl EventBus {
+    /// Create a bus fed by all of the node's managers
+    ///
+    /// Takes the receiver returned by `BluetoothManager::new`, so the bus
+    /// becomes its only consumer.
+    pub fn for_node(
+        capacity: usize,
+        bluetooth_events: mpsc::Receiver<BluetoothEvent>,
+        wallet_manager: &WalletManager,
+        bandwidth_manager: &BandwidthManager,
+    ) -> Self {
+        let mut bus = Self::new(capacity);
+        bus.attach_bluetooth(bluetooth_events);
+        bus.attach_wallet(wallet_manager);
+        bus.attach_bandwidth(bandwidth_manager);
+        bus
+    }
+
+    fn new(capacity: usize) -> Self {
+        Self {
+            sender: broadcast::channel(capacity).0,
+            forwarders: Vec::new(),
+        }
+    }
+
+    /// Subscribe to events from every attached source
+    pub fn subscribe(&self) -> broadcast::Receiver<NodeEvent> {
+        self.sender.subscribe()
+    }
+
+    fn attach_bluetooth(&mut self, mut events: mpsc::Receiver<BluetoothEvent>) {
+        let sender = self.sender.clone();
+        self.forwarders.push(tasks::spawn("event-bus bluetooth", async move {
+            while let Some(event) = events.recv().await {
+                let _ = sender.send(NodeEvent::Bluetooth(event));
+            }
+        }));
+    }
+
+    fn attach_wallet(&mut self, wallet_manager: &WalletManager) {
+        self.forward("event-bus wallet", wallet_manager.subscribe(), NodeEvent::Wallet);
+    }
+
+    fn attach_bandwidth(&mut self, bandwidth_manager: &BandwidthManager) {
+        self.forward("event-bus reward", bandwidth_manager.subscribe_rewards(), NodeEvent::Reward);
+    }
+
+    fn forward<T: Clone + Send + 'static>(
+        &mut self,
+        name: &str,
+        mut events: broadcast::Receiver<T>,
+        tag: fn(T) -> NodeEvent,
+    ) {
+        let sender = self.sender.clone();
+        self.forwarders.push(tasks::spawn(name, async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        let _ = sender.send(tag(event));
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Event bus skipped {} events from a lagging source", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }));
+    }
+}
+
+impl Drop for EventBus {
+    fn drop(&mut self) {
+        for forwarder in &self.forwarders {
+            forwarder.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bandwidth::{MockMeter, RewardRecord},
+        types::CurrencyType,
+    };
+    use chrono::Utc;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    /// Next event on the bus, failing the test if none arrives
+    async fn next(events: &mut broadcast::Receiver<NodeEvent>) -> NodeEvent {
+        tokio::time::timeout(Duration::from_secs(1), events.recv())
+            .await
+            .expect("no event on the bus")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn node_sources_are_tagged_on_the_bus() {
+        let wallet_manager = Arc::new(WalletManager::new());
+        let bandwidth_manager = BandwidthManager::new(wallet_manager.clone(), Box::new(MockMeter::new()));
+        let (bluetooth_sender, bluetooth_events) = mpsc::channel(8);
+        let bus = EventBus::for_node(16, bluetooth_events, &wallet_manager, &bandwidth_manager);
+        let mut events = bus.subscribe();
+
+        bluetooth_sender.send(BluetoothEvent::DeviceConnected("node".to_string())).await.unwrap();
+        assert!(matches!(next(&mut events).await, NodeEvent::Bluetooth(BluetoothEvent::DeviceConnected(name)) if name == "node"));
+
+        let wallet = wallet_manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        wallet_manager.update_wallet_balance(wallet.id, 1.0).await.unwrap();
+        assert!(matches!(next(&mut events).await, NodeEvent::Wallet(WalletEvent::BalanceChanged { wallet_id, .. }) if wallet_id == wallet.id));
+    }
+
+    #[tokio::test]
+    async fn reward_payouts_are_tagged_on_the_bus() {
+        let (rewards, _) = broadcast::channel(8);
+        let mut bus = EventBus::new(16);
+        bus.forward("test reward", rewards.subscribe(), NodeEvent::Reward);
+        let mut events = bus.subscribe();
+
+        let record = RewardRecord {
+            timestamp: Utc::now(),
+            wallet_id: Uuid::new_v4(),
+            currency: CurrencyType::Bitcoin,
+            bytes: 1024,
+            rate_used: 0.001,
+            amount: 0.5,
+        };
+        rewards.send(RewardEvent::Paid(record.clone())).unwrap();
+        assert!(matches!(next(&mut events).await, NodeEvent::Reward(RewardEvent::Paid(paid)) if paid == record));
+    }
+}
@@ -5,6 +5,11 @@ pub mod bandwidth;
 pub mod storage;
 pub mod config;
 pub mod error;
+pub mod events;
+pub mod policy;
+pub mod retry;
+pub mod selftest;
+pub mod tasks;
 pub mod types;
 
 use error::CryptoNodeError;
@@ -36,4 +41,37 @@ pub const CRYPTO_ENABLED: bool = false;
 #[cfg(feature = "bandwidth")]
 pub const BANDWIDTH_ENABLED: bool = true;
 #[cfg(not(feature = "bandwidth"))]
-pub const BANDWIDTH_ENABLED: bool = false; 
\ No newline at end of file
+pub const BANDWIDTH_ENABLED: bool = false; 
+
+/// Features compiled into this build
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct FeatureSet {
+    pub version: &'static str,
+    pub bluetooth: bool,
+    pub crypto: bool,
+    pub bandwidth: bool,
+}
+
+/// Get the features compiled into this build, for advertising to clients
+pub fn features() -> FeatureSet {
+    FeatureSet {
+        version: VERSION,
+        bluetooth: BLUETOOTH_ENABLED,
+        crypto: CRYPTO_ENABLED,
+        bandwidth: BANDWIDTH_ENABLED,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn features_match_compiled_flags() {
+        let features = features();
+        assert_eq!(features.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(features.bluetooth, cfg!(feature = "bluetooth"));
+        assert_eq!(features.crypto, cfg!(feature = "crypto"));
+        assert_eq!(features.bandwidth, cfg!(feature = "bandwidth"));
+    }
+}
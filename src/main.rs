@@ -1,14 +1,19 @@
 use cryptonode::{
     Result,
-    bluetooth::BluetoothManager,
+    bluetooth::{BluetoothManager, DeviceCommand},
     wallet::WalletManager,
-    bandwidth::BandwidthManager,
+    bandwidth::{BandwidthManager, BandwidthMeter, InterfaceMeter, MockMeter, RewardEvent},
     config::ConfigManager,
+    error::CryptoNodeError,
+    events::{EventBus, NodeEvent},
+    selftest::selftest,
     types::CurrencyType,
 };
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal;
-use tracing::{info, error, Level};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn, error, Level};
 use tracing_subscriber::FmtSubscriber;
 
 #[tokio::main]
@@ -16,7 +21,7 @@ async fn main() -> Result<()> {
     // Initialize logging
     let subscriber = FmtSubscriber::builder()
         .with_max_level(Level::INFO)
-        .build();
+        .finish();
     tracing::subscriber::set_global_default(subscriber)
         .expect("Failed to set tracing subscriber");
 
@@ -27,58 +32,135 @@ async fn main() -> Result<()> {
     let config = config_manager.get_config().await?;
     info!("Configuration loaded successfully");
 
-    // Initialize wallet manager
-    let wallet_manager = Arc::new(WalletManager::new());
+    let storage_dir = config_manager.get_config_path().parent()
+        .unwrap_or_else(|| std::path::Path::new("."));
+
+    if config.startup_selftest {
+        let report = selftest(&config, storage_dir).await;
+        for check in &report.checks {
+            match &check.detail {
+                Some(detail) => warn!("Self-test {} failed: {}", check.name, detail),
+                None => info!("Self-test {} passed", check.name),
+            }
+        }
+        if !report.critical_failures().is_empty() {
+            return Err(CryptoNodeError::Device("Startup self-test failed".to_string()));
+        }
+    }
+
+    // Wallets are saved to the auto-backup file, so there is only one copy on disk.
+    // A relative path is resolved against the config directory.
+    let mut backup_config = config.auto_backup.clone();
+    backup_config.path = storage_dir.join(&backup_config.path);
+
+    // Initialize wallet manager, loading saved wallets when a passphrase is available
+    let persistent = match backup_config.passphrase_source.resolve() {
+        Ok(_) => true,
+        // Backups were asked for, so refuse to run without them
+        Err(e) if backup_config.enabled => return Err(e),
+        Err(e) => {
+            error!("Wallet passphrase unavailable ({}); wallets will NOT be saved", e);
+            false
+        }
+    };
+    let wallet_manager = if persistent {
+        WalletManager::load_from_disk(&backup_config.path, backup_config.passphrase_source.clone()).await?
+    } else {
+        WalletManager::new()
+    };
+    let wallet_manager = Arc::new(wallet_manager);
+    let persist_period = Duration::from_secs(backup_config.max_delay_secs.max(1));
+    wallet_manager.configure_auto_backup(backup_config).await;
+    wallet_manager.follow_config(config_manager.watch());
     info!("Wallet manager initialized");
 
     // Initialize bandwidth manager
-    let bandwidth_manager = BandwidthManager::new(wallet_manager.clone());
+    let meter: Box<dyn BandwidthMeter> = match InterfaceMeter::new().await {
+        Ok(meter) => Box::new(meter),
+        Err(e) => {
+            warn!("Cannot read interface counters ({}); using simulated bandwidth", e);
+            Box::new(MockMeter::new())
+        }
+    };
+    let mut bandwidth_manager = BandwidthManager::new(wallet_manager.clone(), meter);
+    bandwidth_manager.update_measurement_interval(Duration::from_secs(config.measurement_interval)).await?;
     info!("Bandwidth manager initialized");
 
     // Initialize Bluetooth
-    let (bluetooth_manager, mut bluetooth_events) = BluetoothManager::new().await?;
+    let (bluetooth_manager, bluetooth_events) = BluetoothManager::new().await?;
     info!("Bluetooth manager initialized");
 
+    // Merge manager events into a single stream
+    let event_bus = EventBus::for_node(256, bluetooth_events, &wallet_manager, &bandwidth_manager);
+    let mut node_events = event_bus.subscribe();
+
     // Start Bluetooth scanning
     bluetooth_manager.start_scan().await?;
     info!("Bluetooth scanning started");
 
-    // Create default wallet if none exists
-    let wallets = wallet_manager.list_wallets().await?;
-    if wallets.is_empty() {
-        info!("Creating default wallet...");
-        let wallet = wallet_manager.create_wallet(CurrencyType::Bitcoin).await?;
-        info!("Created default wallet with ID: {}", wallet.id);
-
-        // Start bandwidth monitoring for the default wallet
-        bandwidth_manager.start_monitoring(wallet.id).await?;
-        info!("Bandwidth monitoring started for wallet: {}", wallet.id);
+    // Use the default wallet, creating it if none exists
+    let wallet = wallet_manager.get_or_create_wallet(CurrencyType::Bitcoin).await?;
+    info!("Using default wallet with ID: {} (fingerprint {})", wallet.id, wallet.fingerprint());
+    if persistent {
+        wallet_manager.persist().await?;
     }
 
+    // Start bandwidth monitoring for the default wallet
+    bandwidth_manager.start_monitoring(wallet.id).await?;
+    info!("Bandwidth monitoring started for wallet: {}", wallet.id);
+
+    // Save periodically too, so a crash loses at most one period of changes
+    let mut persist_tick = tokio::time::interval(persist_period);
+
     // Main event loop
     info!("Entering main event loop...");
     loop {
         tokio::select! {
-            // Handle Bluetooth events
-            Some(event) = bluetooth_events.recv() => {
+            _ = persist_tick.tick(), if persistent => {
+                if let Err(e) = wallet_manager.persist().await {
+                    warn!("Failed to save wallets: {}", e);
+                }
+            }
+
+            // Handle events from every manager
+            event = node_events.recv() => {
                 match event {
-                    cryptonode::bluetooth::BluetoothEvent::DeviceDiscovered(name) => {
-                        info!("Discovered Bluetooth device: {}", name);
-                    }
-                    cryptonode::bluetooth::BluetoothEvent::DeviceConnected(name) => {
-                        info!("Connected to Bluetooth device: {}", name);
-                    }
-                    cryptonode::bluetooth::BluetoothEvent::DeviceDisconnected(name) => {
-                        info!("Disconnected from Bluetooth device: {}", name);
+                    Ok(NodeEvent::Bluetooth(event)) => match event {
+                        cryptonode::bluetooth::BluetoothEvent::DeviceDiscovered(name) => {
+                            info!("Discovered Bluetooth device: {}", name);
+                        }
+                        cryptonode::bluetooth::BluetoothEvent::DeviceConnected(name) => {
+                            info!("Connected to Bluetooth device: {}", name);
+                        }
+                        cryptonode::bluetooth::BluetoothEvent::DeviceDisconnected(name) => {
+                            info!("Disconnected from Bluetooth device: {}", name);
+                        }
+                        cryptonode::bluetooth::BluetoothEvent::DataReceived(data) => {
+                            info!("Received {} bytes of data", data.len());
+                            if let Err(e) = handle_command(&data, &wallet_manager, &bluetooth_manager).await {
+                                warn!("Failed to handle command: {}", e);
+                            }
+                        }
+                        cryptonode::bluetooth::BluetoothEvent::Error(err) => {
+                            error!("Bluetooth error: {}", err);
+                        }
+                        cryptonode::bluetooth::BluetoothEvent::ScanRestarted => {
+                            warn!("Bluetooth scan restarted by watchdog");
+                        }
+                        cryptonode::bluetooth::BluetoothEvent::AdapterReset => {
+                            warn!("Bluetooth adapter reset after repeated errors");
+                        }
+                    },
+                    Ok(NodeEvent::Wallet(event)) => {
+                        info!("Wallet event: {:?}", event);
                     }
-                    cryptonode::bluetooth::BluetoothEvent::DataReceived(data) => {
-                        info!("Received {} bytes of data", data.len());
-                        // Handle received data
-                        // TODO: Implement command processing
+                    Ok(NodeEvent::Reward(RewardEvent::Paid(record))) => {
+                        info!("Reward of {} credited to wallet {}", record.amount, record.wallet_id);
                     }
-                    cryptonode::bluetooth::BluetoothEvent::Error(err) => {
-                        error!("Bluetooth error: {}", err);
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("Main loop skipped {} events", skipped);
                     }
+                    Err(RecvError::Closed) => break,
                 }
             }
 
@@ -90,10 +172,45 @@ async fn main() -> Result<()> {
         }
     }
 
-    // Cleanup
+    // Cleanup. Wallets are saved first so no other failure can lose them.
     info!("Shutting down...");
-    bluetooth_manager.disconnect().await?;
-    info!("Bluetooth disconnected");
+    match wallet_manager.shutdown().await {
+        Ok(()) => info!("Wallet state flushed"),
+        Err(e) => warn!("Failed to flush wallet backup: {}", e),
+    }
+    let saved = if persistent {
+        wallet_manager.persist().await
+    } else {
+        Ok(())
+    };
+    match &saved {
+        Ok(()) if persistent => info!("Wallets saved"),
+        Ok(()) => {}
+        Err(e) => error!("Failed to save wallets: {}", e),
+    }
+    match bluetooth_manager.disconnect().await {
+        Ok(()) => info!("Bluetooth disconnected"),
+        Err(e) => warn!("Bluetooth disconnect failed: {}", e),
+    }
+    // Flushing needs the backup task, so background tasks are stopped last
+    if let Err(e) = cryptonode::tasks::registry().shutdown(Duration::from_secs(5)).await {
+        warn!("Background tasks did not stop: {}", e);
+    }
+
+    saved
+} 
 
-    Ok(())
-} 
\ No newline at end of file
+/// Answer a command received from the connected controller
+async fn handle_command(data: &[u8], wallet_manager: &WalletManager, bluetooth_manager: &BluetoothManager) -> Result<()> {
+    let Some(command) = bluetooth_manager.receive_command(data).await? else {
+        return Ok(());
+    };
+    let response = match command {
+        DeviceCommand::GetBalance { wallet_id } => {
+            let view = wallet_manager.balance_view(wallet_id).await?;
+            serde_json::to_vec(&view)
+                .map_err(|e| CryptoNodeError::Serialization(e.to_string()))?
+        }
+    };
+    bluetooth_manager.send_response(&response).await
+}
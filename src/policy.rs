@@ -0,0 +1,75 @@
+use crate::{
+    Result,
+    error::CryptoNodeError,
+    types::CurrencyType,
+};
+use std::sync::OnceLock;
+
+static CURRENCY_POLICY: OnceLock<CurrencyPolicy> = OnceLock::new();
+
+/// Process-wide restriction on which currencies may be used
+///
+/// Unlike `DeviceConfig::supported_currencies` this cannot be changed at
+/// runtime; it is meant for compliance blocks set once at startup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CurrencyPolicy {
+    /// Only these currencies are permitted
+    Allow(Vec<CurrencyType>),
+    /// These currencies are blocked, all others are permitted
+    Deny(Vec<CurrencyType>),
+}
+
+impl CurrencyPolicy {
+    /// Whether the policy permits a currency
+    pub fn permits(&self, currency: &CurrencyType) -> bool {
+        match self {
+            CurrencyPolicy::Allow(allowed) => allowed.contains(currency),
+            CurrencyPolicy::Deny(denied) => !denied.contains(currency),
+        }
+    }
+}
+
+/// Install the process-wide currency policy
+///
+/// Can only be called once; later calls are rejected.
+pub fn set_currency_policy(policy: CurrencyPolicy) -> Result<()> {
+    CURRENCY_POLICY.set(policy)
+        .map_err(|_| CryptoNodeError::Config("Currency policy is already set".to_string()))
+}
+
+/// The installed currency policy, if any
+pub fn currency_policy() -> Option<&'static CurrencyPolicy> {
+    CURRENCY_POLICY.get()
+}
+
+/// Fail with `PermissionDenied` if the installed policy blocks a currency
+pub fn ensure_currency_permitted(currency: &CurrencyType) -> Result<()> {
+    match CURRENCY_POLICY.get() {
+        Some(policy) if !policy.permits(currency) => Err(CryptoNodeError::PermissionDenied(format!(
+            "{} is blocked by the currency policy", currency.display_name()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The installed policy is process-wide, so only `permits` is exercised here
+
+    #[test]
+    fn allow_list_permits_only_listed_currencies() {
+        let policy = CurrencyPolicy::Allow(vec![CurrencyType::Bitcoin]);
+        assert!(policy.permits(&CurrencyType::Bitcoin));
+        assert!(!policy.permits(&CurrencyType::Ethereum));
+    }
+
+    #[test]
+    fn deny_list_blocks_only_listed_currencies() {
+        let policy = CurrencyPolicy::Deny(vec![CurrencyType::Ethereum]);
+        assert!(policy.permits(&CurrencyType::Bitcoin));
+        assert!(!policy.permits(&CurrencyType::Ethereum));
+        assert!(CurrencyPolicy::Deny(Vec::new()).permits(&CurrencyType::Ethereum));
+    }
+}
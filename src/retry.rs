@@ -0,0 +1,173 @@
+use crate::{Result, error::CryptoNodeError};
+use std::future::Future;
+use tokio::time::{Duration, sleep, timeout};
+
+/// Retry and timeout behavior shared by Bluetooth and storage operations
+///
+/// The crate has no network backend yet; one should run its requests through
+/// `retry_with_policy` too so failures are classified by `is_retryable`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that makes a single attempt
+    pub fn no_retry(timeout: Duration) -> Self {
+        Self {
+            max_attempts: 1,
+            timeout,
+            ..Self::default()
+        }
+    }
+
+    /// Delay to wait after the given failed attempt (1-based)
+    ///
+    /// Doubles from `base_delay` on each attempt, capped at `max_delay`.
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        self.base_delay
+            .checked_mul(1u32 << exponent)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+}
+
+/// Run an operation under a retry policy
+///
+/// Each attempt is bounded by `policy.timeout`. Failures are retried with
+/// exponential backoff only while `CryptoNodeError::is_retryable` holds.
+pub async fn retry_with_policy<T, F, Fut>(policy: &RetryPolicy, mut op: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+
+    loop {
+        let err = match timeout(policy.timeout, op()).await {
+            Ok(Ok(value)) => return Ok(value),
+            Ok(Err(e)) => e,
+            Err(_) => CryptoNodeError::Timeout,
+        };
+
+        if attempt >= max_attempts || !err.is_retryable() {
+            return Err(err);
+        }
+
+        sleep(policy.delay_for(attempt)).await;
+        attempt += 1;
+    }
+}
+
+/// Blocking counterpart of `retry_with_policy` for synchronous operations
+///
+/// Uses the same backoff and retryability rules, but `policy.timeout` is not
+/// enforced since a blocking call can't be interrupted.
+pub fn retry_blocking_with_policy<T, F>(policy: &RetryPolicy, mut op: F) -> Result<T>
+where
+    F: FnMut() -> Result<T>,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+
+    loop {
+        let err = match op() {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+
+        if attempt >= max_attempts || !err.is_retryable() {
+            return Err(err);
+        }
+
+        std::thread::sleep(policy.delay_for(attempt));
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(4),
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max_delay() {
+        let policy = policy(10);
+        let delays: Vec<u64> = (1..=5).map(|a| policy.delay_for(a).as_millis() as u64).collect();
+        assert_eq!(delays, vec![1, 2, 4, 4, 4]);
+        assert_eq!(policy.delay_for(u32::MAX), Duration::from_millis(4));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let attempts = Cell::new(0);
+        let result: Result<()> = retry_with_policy(&policy(3), || {
+            attempts.set(attempts.get() + 1);
+            async { Err(CryptoNodeError::Bluetooth("flaky".to_string())) }
+        }).await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_permanent_errors() {
+        let attempts = Cell::new(0);
+        let result: Result<()> = retry_with_policy(&policy(3), || {
+            attempts.set(attempts.get() + 1);
+            async { Err(CryptoNodeError::InvalidInput("bad".to_string())) }
+        }).await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn times_out_slow_attempts() {
+        let policy = RetryPolicy { timeout: Duration::from_millis(5), ..policy(1) };
+        let result = retry_with_policy(&policy, || async {
+            sleep(Duration::from_secs(1)).await;
+            Ok(())
+        }).await;
+        assert!(matches!(result, Err(CryptoNodeError::Timeout)));
+    }
+
+    #[test]
+    fn blocking_retry_succeeds_after_transient_errors() {
+        let attempts = Cell::new(0);
+        let result = retry_blocking_with_policy(&policy(3), || {
+            attempts.set(attempts.get() + 1);
+            if attempts.get() < 3 {
+                Err(CryptoNodeError::Timeout)
+            } else {
+                Ok(attempts.get())
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+}
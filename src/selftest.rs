@@ -0,0 +1,156 @@
+use crate::{
+    BLUETOOTH_ENABLED,
+    Result,
+    config::ConfigManager,
+    crypto::{self, SigningAlgorithm},
+    error::CryptoNodeError,
+    storage,
+    types::DeviceConfig,
+};
+use btleplug::api::Manager as _;
+use btleplug::platform::Manager;
+use std::fs;
+use std::path::Path;
+
+/// RFC 8032 section 7.1, test 1: secret key, public key and signature of the empty message
+const KAT_SECRET_KEY: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+const KAT_PUBLIC_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+const KAT_SIGNATURE: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+
+/// secp256k1 with RFC 6979 nonces: secret key 1 (public key G) signing "Satoshi Nakamoto"
+const SECP256K1_KAT_SECRET_KEY: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+const SECP256K1_KAT_PUBLIC_KEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+const SECP256K1_KAT_MESSAGE: &[u8] = b"Satoshi Nakamoto";
+const SECP256K1_KAT_SIGNATURE: &str = "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d82442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5";
+
+/// Outcome of a single self-test check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestCheck {
+    pub name: &'static str,
+    pub passed: bool,
+    pub critical: bool, // Startup should abort if this fails
+    pub detail: Option<String>,
+}
+
+/// Results of `selftest`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelfTestReport {
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    /// Whether every check passed
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+
+    /// Failed checks that should abort startup
+    pub fn critical_failures(&self) -> Vec<&SelfTestCheck> {
+        self.checks.iter().filter(|c| c.critical && !c.passed).collect()
+    }
+
+    fn record(&mut self, name: &'static str, critical: bool, result: Result<()>) {
+        let (passed, detail) = match result {
+            Ok(()) => (true, None),
+            Err(e) => (false, Some(e.to_string())),
+        };
+        self.checks.push(SelfTestCheck { name, passed, critical, detail });
+    }
+}
+
+/// Check the environment is usable before starting the node
+///
+/// Validates the config, probes that `storage_dir` is writable, looks for a
+/// Bluetooth adapter when Bluetooth support is compiled in, and checks both
+/// signing algorithms against known-answer tests.
+pub async fn selftest(config: &DeviceConfig, storage_dir: &Path) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+
+    report.record("config", true, ConfigManager::validate(config));
+    report.record("storage", true, check_storage(storage_dir));
+    if BLUETOOTH_ENABLED {
+        report.record("bluetooth", false, check_bluetooth().await);
+    }
+    report.record("crypto", true, check_crypto());
+
+    report
+}
+
+fn check_storage(storage_dir: &Path) -> Result<()> {
+    let probe = storage_dir.join(".cryptonode-selftest");
+    storage::write_atomic(&probe, b"selftest")?;
+    fs::remove_file(&probe)
+        .map_err(|e| CryptoNodeError::Storage(format!("Failed to remove {}: {}", probe.display(), e)))
+}
+
+async fn check_bluetooth() -> Result<()> {
+    let manager = Manager::new().await
+        .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))?;
+    let adapters = manager.adapters().await
+        .map_err(|e| CryptoNodeError::Bluetooth(e.to_string()))?;
+    if adapters.is_empty() {
+        return Err(CryptoNodeError::Bluetooth("No Bluetooth adapter found".to_string()));
+    }
+    Ok(())
+}
+
+fn check_crypto() -> Result<()> {
+    let decode = |s: &str| hex::decode(s).map_err(|e| CryptoNodeError::CryptoOperation(e.to_string()));
+    let secret_key = decode(KAT_SECRET_KEY)?;
+    let public_key = decode(KAT_PUBLIC_KEY)?;
+    let expected = decode(KAT_SIGNATURE)?;
+
+    let derived = crypto::signing_key_from_bytes(&secret_key)?.verifying_key();
+    if derived.as_bytes()[..] != public_key[..] {
+        return Err(CryptoNodeError::CryptoOperation("Known-answer public key mismatch".to_string()));
+    }
+    if crypto::sign(&secret_key, b"")? != expected {
+        return Err(CryptoNodeError::CryptoOperation("Known-answer signature mismatch".to_string()));
+    }
+    if !crypto::verify(&public_key, b"", &expected)? {
+        return Err(CryptoNodeError::CryptoOperation("Known-answer verification failed".to_string()));
+    }
+
+    let secret_key = decode(SECP256K1_KAT_SECRET_KEY)?;
+    let public_key = decode(SECP256K1_KAT_PUBLIC_KEY)?;
+    let expected = decode(SECP256K1_KAT_SIGNATURE)?;
+    let algorithm = SigningAlgorithm::Secp256k1;
+    if algorithm.public_key(&secret_key)? != public_key {
+        return Err(CryptoNodeError::CryptoOperation("secp256k1 known-answer public key mismatch".to_string()));
+    }
+    if algorithm.sign(&secret_key, SECP256K1_KAT_MESSAGE)? != expected {
+        return Err(CryptoNodeError::CryptoOperation("secp256k1 known-answer signature mismatch".to_string()));
+    }
+    if !algorithm.verify(&public_key, SECP256K1_KAT_MESSAGE, &expected)? {
+        return Err(CryptoNodeError::CryptoOperation("secp256k1 known-answer verification failed".to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crypto_known_answers_pass() {
+        check_crypto().unwrap();
+    }
+
+    #[test]
+    fn storage_probe_is_cleaned_up() {
+        let dir = std::env::temp_dir().join(format!("cryptonode-selftest-{}", uuid::Uuid::new_v4()));
+        check_storage(&dir).unwrap();
+        assert!(!dir.join(".cryptonode-selftest").exists());
+    }
+
+    #[tokio::test]
+    async fn invalid_config_is_a_critical_failure() {
+        let dir = std::env::temp_dir().join(format!("cryptonode-selftest-{}", uuid::Uuid::new_v4()));
+        let config = DeviceConfig {
+            device_name: String::new(),
+            ..DeviceConfig::default()
+        };
+        let report = selftest(&config, &dir).await;
+        assert!(report.critical_failures().iter().any(|c| c.name == "config"));
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    Result,
+    crypto,
+    error::CryptoNodeError,
+    retry::{RetryPolicy, retry_blocking_with_policy},
+    types::{Transaction, Wallet},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Magic bytes identifying a CryptoNode wallet file
+const FILE_MAGIC: &[u8; 4] = b"CNWL";
+
+/// Current wallet file format version
+const FILE_VERSION: u16 = 1;
+
+/// Header size: magic, version, payload length and SHA-256 digest
+const HEADER_LEN: usize = 4 + 2 + 8 + 32;
+
+/// Where to obtain the passphrase used to encrypt backups
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PassphraseSource {
+    /// Read from an environment variable
+    Env(String),
+    /// Read from the first line of a file
+    File(PathBuf),
+}
+
+impl Default for PassphraseSource {
+    fn default() -> Self {
+        PassphraseSource::Env("CRYPTONODE_BACKUP_PASSPHRASE".to_string())
+    }
+}
+
+impl PassphraseSource {
+    /// Resolve the passphrase
+    pub fn resolve(&self) -> Result<String> {
+        let passphrase = match self {
+            PassphraseSource::Env(var) => std::env::var(var)
+                .map_err(|_| CryptoNodeError::Config(format!("Passphrase variable {} is not set", var)))?,
+            PassphraseSource::File(path) => fs::read_to_string(path)
+                .map_err(|e| CryptoNodeError::Storage(format!("Failed to read passphrase file: {}", e)))?
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        };
+
+        if passphrase.is_empty() {
+            return Err(CryptoNodeError::Config("Backup passphrase is empty".to_string()));
+        }
+        Ok(passphrase)
+    }
+}
+
+/// Wallet and transaction state captured in a backup
+///
+/// Deliberately not `Serialize`: the only way to persist it is `backup`,
+/// which includes private keys and therefore always encrypts.
+#[derive(Debug, Clone)]
+pub struct WalletBackup {
+    pub wallets: Vec<Wallet>,
+    pub transactions: Vec<Transaction>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Plaintext inside an encrypted backup
+#[derive(Serialize, Deserialize)]
+struct BackupPayload {
+    wallets: Vec<KeyedWallet>,
+    transactions: Vec<Transaction>,
+    created_at: DateTime<Utc>,
+}
+
+/// A wallet serialized together with its private key
+#[derive(Serialize, Deserialize)]
+struct KeyedWallet {
+    #[serde(flatten)]
+    wallet: Wallet,
+    private_key: String, // Hex encoded
+}
+
+impl From<&WalletBackup> for BackupPayload {
+    fn from(backup: &WalletBackup) -> Self {
+        Self {
+            wallets: backup.wallets.iter()
+                .map(|wallet| KeyedWallet {
+                    wallet: wallet.clone(),
+                    private_key: hex::encode(&wallet.private_key),
+                })
+                .collect(),
+            transactions: backup.transactions.clone(),
+            created_at: backup.created_at,
+        }
+    }
+}
+
+impl TryFrom<BackupPayload> for WalletBackup {
+    type Error = CryptoNodeError;
+
+    fn try_from(payload: BackupPayload) -> Result<Self> {
+        for transaction in &payload.transactions {
+            transaction.check_amounts()?;
+        }
+        let wallets = payload.wallets.into_iter()
+            .map(|keyed| {
+                let mut wallet = keyed.wallet;
+                wallet.check_amounts()?;
+                wallet.private_key = hex::decode(&keyed.private_key)
+                    .map_err(|e| CryptoNodeError::Serialization(format!("Invalid private key in backup: {}", e)))?;
+                Ok(wallet)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            wallets,
+            transactions: payload.transactions,
+            created_at: payload.created_at,
+        })
+    }
+}
+
+/// Write an encrypted backup to `path`
+///
+/// The file is written to a temporary sibling first and renamed into place
+/// so an interrupted write never leaves a truncated backup behind.
+pub fn backup(path: &Path, data: &WalletBackup, passphrase: &str) -> Result<()> {
+    let plaintext = serde_json::to_vec(&BackupPayload::from(data))
+        .map_err(|e| CryptoNodeError::Serialization(format!("Failed to serialize backup: {}", e)))?;
+    let encrypted = crypto::encrypt_with_passphrase(passphrase, &plaintext)?;
+    write_atomic(path, &encode_file(&encrypted))
+}
+
+/// Read and decrypt a backup written by `backup`
+pub fn restore_backup(path: &Path, passphrase: &str) -> Result<WalletBackup> {
+    let contents = fs::read(path)
+        .map_err(|e| CryptoNodeError::Storage(format!("Failed to read backup: {}", e)))?;
+    let encrypted = decode_file(&contents)?;
+    let plaintext = crypto::decrypt_with_passphrase(passphrase, encrypted)?;
+    let payload: BackupPayload = serde_json::from_slice(&plaintext)
+        .map_err(|e| CryptoNodeError::Serialization(format!("Failed to parse backup: {}", e)))?;
+    WalletBackup::try_from(payload)
+}
+
+/// Wrap a payload in the wallet file header
+///
+/// Layout: 4-byte magic `CNWL`, u16 format version, u64 payload length,
+/// 32-byte SHA-256 of the payload, then the payload. Integers are big-endian.
+pub fn encode_file(payload: &[u8]) -> Vec<u8> {
+    let mut contents = Vec::with_capacity(HEADER_LEN + payload.len());
+    contents.extend_from_slice(FILE_MAGIC);
+    contents.extend_from_slice(&FILE_VERSION.to_be_bytes());
+    contents.extend_from_slice(&(payload.len() as u64).to_be_bytes());
+    contents.extend_from_slice(&Sha256::digest(payload));
+    contents.extend_from_slice(payload);
+    contents
+}
+
+/// Verify a wallet file header and return its payload
+pub fn decode_file(contents: &[u8]) -> Result<&[u8]> {
+    let corrupt = || CryptoNodeError::Storage("corrupt file".to_string());
+
+    if contents.len() < HEADER_LEN || &contents[..4] != FILE_MAGIC {
+        return Err(corrupt());
+    }
+
+    let version = u16::from_be_bytes([contents[4], contents[5]]);
+    if version != FILE_VERSION {
+        return Err(CryptoNodeError::Storage(format!("Unsupported file version {}", version)));
+    }
+
+    let mut length = [0u8; 8];
+    length.copy_from_slice(&contents[6..14]);
+    let payload = &contents[HEADER_LEN..];
+    if u64::from_be_bytes(length) != payload.len() as u64 {
+        return Err(corrupt());
+    }
+
+    if Sha256::digest(payload).as_slice() != &contents[14..HEADER_LEN] {
+        return Err(corrupt());
+    }
+
+    Ok(payload)
+}
+
+/// Write a file by renaming a fully written temporary file into place
+///
+/// Transient I/O errors are retried under the default `RetryPolicy`.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    write_atomic_with_policy(path, contents, &RetryPolicy::default())
+}
+
+/// `write_atomic` with an explicit retry policy
+pub fn write_atomic_with_policy(path: &Path, contents: &[u8], policy: &RetryPolicy) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        retry_blocking_with_policy(policy, || {
+            fs::create_dir_all(parent)
+                .map_err(|e| io_error(e, "Failed to create directory"))
+        })?;
+    }
+
+    let mut tmp_name = path.as_os_str().to_owned();
+    tmp_name.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_name);
+
+    retry_blocking_with_policy(policy, || {
+        fs::write(&tmp_path, contents)
+            .map_err(|e| io_error(e, &format!("Failed to write {}", tmp_path.display())))?;
+        fs::rename(&tmp_path, path)
+            .map_err(|e| io_error(e, &format!("Failed to replace {}", path.display())))
+    })
+}
+
+/// Keep transient I/O errors as `Io` so they're retried; anything else is a storage error
+fn io_error(err: io::Error, context: &str) -> CryptoNodeError {
+    match err.kind() {
+        io::ErrorKind::Interrupted
+        | io::ErrorKind::WouldBlock
+        | io::ErrorKind::TimedOut
+        | io::ErrorKind::ResourceBusy => CryptoNodeError::Io(err),
+        _ => CryptoNodeError::Storage(format!("{}: {}", context, err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_header_round_trips() {
+        let contents = encode_file(b"wallet state");
+        assert_eq!(&contents[..4], FILE_MAGIC);
+        assert_eq!(decode_file(&contents).unwrap(), b"wallet state");
+    }
+
+    #[test]
+    fn truncated_or_altered_files_are_corrupt() {
+        let contents = encode_file(b"wallet state");
+
+        let truncated = &contents[..contents.len() - 1];
+        let mut flipped = contents.clone();
+        *flipped.last_mut().unwrap() ^= 1;
+        let mut wrong_magic = contents.clone();
+        wrong_magic[0] = b'X';
+
+        for bad in [truncated, &flipped[..], &wrong_magic[..], &contents[..HEADER_LEN - 1]] {
+            assert!(matches!(decode_file(bad), Err(CryptoNodeError::Storage(message)) if message == "corrupt file"));
+        }
+    }
+
+    #[test]
+    fn newer_file_versions_are_rejected() {
+        let mut contents = encode_file(b"wallet state");
+        contents[4..6].copy_from_slice(&(FILE_VERSION + 1).to_be_bytes());
+
+        assert!(matches!(decode_file(&contents), Err(CryptoNodeError::Storage(message)) if message.contains("version")));
+    }
+}
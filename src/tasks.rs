@@ -0,0 +1,175 @@
+use crate::{
+    Result,
+    error::CryptoNodeError,
+};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::watch;
+use tokio::task::{AbortHandle, JoinHandle};
+use tokio::time::{Duration, timeout};
+
+static REGISTRY: LazyLock<TaskRegistry> = LazyLock::new(TaskRegistry::new);
+
+/// A background task known to a `TaskRegistry`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskInfo {
+    pub id: u64,
+    pub name: String,
+}
+
+struct TaskEntry {
+    name: String,
+    abort: Option<AbortHandle>, // Set right after spawning
+}
+
+/// Tracks spawned background tasks so they can be listed, aborted and drained
+///
+/// Tasks deregister themselves when they finish or are aborted.
+#[derive(Clone)]
+pub struct TaskRegistry {
+    tasks: Arc<Mutex<HashMap<u64, TaskEntry>>>,
+    next_id: Arc<AtomicU64>,
+    active: Arc<watch::Sender<usize>>,
+}
+
+impl Default for TaskRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaskRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            tasks: Arc::new(Mutex::new(HashMap::new())),
+            next_id: Arc::new(AtomicU64::new(1)),
+            active: Arc::new(watch::channel(0).0),
+        }
+    }
+
+    /// Spawn a task on the tokio runtime and register it under `name`
+    pub fn spawn<F>(&self, name: impl Into<String>, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        // Register first so a task that finishes immediately still deregisters cleanly
+        self.lock().insert(id, TaskEntry { name: name.into(), abort: None });
+        self.active.send_modify(|active| *active += 1);
+
+        let guard = Deregister { registry: self.clone(), id };
+        let handle = tokio::spawn(async move {
+            let _guard = guard;
+            future.await
+        });
+        if let Some(entry) = self.lock().get_mut(&id) {
+            entry.abort = Some(handle.abort_handle());
+        }
+        handle
+    }
+
+    /// Running tasks, oldest first
+    pub fn list_tasks(&self) -> Vec<TaskInfo> {
+        let mut tasks: Vec<TaskInfo> = self.lock().iter()
+            .map(|(&id, entry)| TaskInfo { id, name: entry.name.clone() })
+            .collect();
+        tasks.sort_by_key(|task| task.id);
+        tasks
+    }
+
+    /// Abort a running task
+    pub fn abort_task(&self, id: u64) -> Result<()> {
+        let tasks = self.lock();
+        let entry = tasks.get(&id)
+            .ok_or_else(|| CryptoNodeError::NotFound(format!("Task {} not found", id)))?;
+        if let Some(abort) = &entry.abort {
+            abort.abort();
+        }
+        Ok(())
+    }
+
+    /// Abort every task and wait up to `limit` for all of them to finish
+    pub async fn shutdown(&self, limit: Duration) -> Result<()> {
+        for entry in self.lock().values() {
+            if let Some(abort) = &entry.abort {
+                abort.abort();
+            }
+        }
+
+        let mut active = self.active.subscribe();
+        let drained = timeout(limit, active.wait_for(|&active| active == 0)).await.is_ok();
+        if !drained {
+            return Err(CryptoNodeError::Timeout);
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, TaskEntry>> {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Removes a task from its registry when the task's future is dropped
+struct Deregister {
+    registry: TaskRegistry,
+    id: u64,
+}
+
+impl Drop for Deregister {
+    fn drop(&mut self) {
+        if self.registry.lock().remove(&self.id).is_some() {
+            self.registry.active.send_modify(|active| *active -= 1);
+        }
+    }
+}
+
+/// The process-wide registry used by the crate's managers
+pub fn registry() -> &'static TaskRegistry {
+    &REGISTRY
+}
+
+/// Spawn a task registered with the process-wide registry
+pub fn spawn<F>(name: impl Into<String>, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    REGISTRY.spawn(name, future)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn finished_and_aborted_tasks_deregister() {
+        let registry = TaskRegistry::new();
+        registry.spawn("quick", async {}).await.unwrap();
+        assert!(registry.list_tasks().is_empty());
+
+        let handle = registry.spawn("sleeper", tokio::time::sleep(Duration::from_secs(60)));
+        let tasks = registry.list_tasks();
+        assert_eq!(tasks.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["sleeper"]);
+
+        registry.abort_task(tasks[0].id).unwrap();
+        assert!(handle.await.unwrap_err().is_cancelled());
+        assert!(registry.list_tasks().is_empty());
+        assert!(matches!(registry.abort_task(tasks[0].id), Err(CryptoNodeError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn shutdown_aborts_and_drains_every_task() {
+        let registry = TaskRegistry::new();
+        for name in ["first", "second"] {
+            registry.spawn(name, tokio::time::sleep(Duration::from_secs(60)));
+        }
+        assert_eq!(registry.list_tasks().len(), 2);
+
+        registry.shutdown(Duration::from_secs(1)).await.unwrap();
+        assert!(registry.list_tasks().is_empty());
+    }
+}
@@ -1,7 +1,10 @@
-use serde::{Deserialize, Serialize};
+use crate::{Result, crypto::SigningAlgorithm, error::CryptoNodeError, storage::PassphraseSource};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// Represents a cryptocurrency wallet
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,20 +12,284 @@ pub struct Wallet {
     pub id: Uuid,
     pub address: String,
     pub public_key: Vec<u8>,
-    #[serde(skip_serializing)]
-    pub private_key: Vec<u8>,
+    #[serde(skip_serializing, default)]
+    pub private_key: Vec<u8>, // Only persisted through `storage::backup`
+    #[serde(default)]
+    pub signing_algorithm: SigningAlgorithm, // Wallets from before algorithm selection are ed25519
     pub currency_type: CurrencyType,
     pub balance: f64,
     pub created_at: DateTime<Utc>,
     pub last_updated: DateTime<Utc>,
+    #[serde(default)]
+    pub frozen: bool,
+    #[serde(default)]
+    pub receive_addresses: Vec<String>, // Derived invoice addresses, in index order
+}
+
+impl Wallet {
+    /// Reject a non-finite or negative balance, e.g. from corrupted storage
+    pub fn check_amounts(&self) -> Result<()> {
+        check_monetary("balance", self.balance)
+    }
+
+    /// Short stable identifier safe to show in logs and UIs
+    ///
+    /// The first 8 hex characters of the SHA-256 of the public key.
+    pub fn fingerprint(&self) -> String {
+        let digest = Sha256::digest(&self.public_key);
+        hex::encode(&digest[..4])
+    }
+}
+
+/// Just enough of a wallet to show its balance, for clients on constrained links
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceView {
+    pub id: Uuid,
+    pub currency: CurrencyType,
+    pub balance: f64,
+    pub last_updated: DateTime<Utc>,
+}
+
+impl From<&Wallet> for BalanceView {
+    fn from(wallet: &Wallet) -> Self {
+        Self {
+            id: wallet.id,
+            currency: wallet.currency_type.clone(),
+            balance: wallet.balance,
+            last_updated: wallet.last_updated,
+        }
+    }
+}
+
+/// Wallet details without key material, safe to hand to UIs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalletView {
+    pub id: Uuid,
+    pub address: String,
+    pub public_key: Vec<u8>,
+    pub currency_type: CurrencyType,
+    pub balance: f64,
+    pub created_at: DateTime<Utc>,
+    pub last_updated: DateTime<Utc>,
+    pub frozen: bool,
+}
+
+impl From<&Wallet> for WalletView {
+    fn from(wallet: &Wallet) -> Self {
+        Self {
+            id: wallet.id,
+            address: wallet.address.clone(),
+            public_key: wallet.public_key.clone(),
+            currency_type: wallet.currency_type.clone(),
+            balance: wallet.balance,
+            created_at: wallet.created_at,
+            last_updated: wallet.last_updated,
+            frozen: wallet.frozen,
+        }
+    }
 }
 
 /// Supported cryptocurrency types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum CurrencyType {
     Bitcoin,
     Ethereum,
+    /// A token identified by its contract address
+    Token {
+        contract: String,
+        symbol: String,
+        name: String,
+        decimals: u32,
+    },
     // Add more currencies as needed
+    /// A currency written by a newer build, kept as a read-only placeholder
+    Unknown(String),
+}
+
+/// Serialized form of the currencies this build understands
+#[derive(Serialize, Deserialize)]
+enum KnownCurrency {
+    Bitcoin,
+    Ethereum,
+    Token {
+        contract: String,
+        symbol: String,
+        name: String,
+        decimals: u32,
+    },
+}
+
+const KNOWN_CURRENCIES: &[&str] = &["Bitcoin", "Ethereum", "Token"];
+
+impl Serialize for CurrencyType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let known = match self.clone() {
+            CurrencyType::Bitcoin => KnownCurrency::Bitcoin,
+            CurrencyType::Ethereum => KnownCurrency::Ethereum,
+            CurrencyType::Token { contract, symbol, name, decimals } => {
+                KnownCurrency::Token { contract, symbol, name, decimals }
+            }
+            // Written back under its original name so newer builds still recognize it
+            CurrencyType::Unknown(name) => return serializer.serialize_str(&name),
+        };
+        known.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CurrencyType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let name = match &value {
+            serde_json::Value::String(name) => name.clone(),
+            serde_json::Value::Object(map) if map.len() == 1 => map.keys().next().cloned().unwrap_or_default(),
+            _ => return Err(serde::de::Error::custom("invalid currency type")),
+        };
+
+        // Unrecognized variants load as placeholders instead of failing the whole store
+        if !KNOWN_CURRENCIES.contains(&name.as_str()) {
+            return Ok(CurrencyType::Unknown(name));
+        }
+
+        let known = KnownCurrency::deserialize(value).map_err(serde::de::Error::custom)?;
+        Ok(match known {
+            KnownCurrency::Bitcoin => CurrencyType::Bitcoin,
+            KnownCurrency::Ethereum => CurrencyType::Ethereum,
+            KnownCurrency::Token { contract, symbol, name, decimals } => {
+                CurrencyType::Token { contract, symbol, name, decimals }
+            }
+        })
+    }
+}
+
+impl CurrencyType {
+    /// Ticker symbol, e.g. "BTC"
+    pub fn symbol(&self) -> &str {
+        match self {
+            CurrencyType::Bitcoin => "BTC",
+            CurrencyType::Ethereum => "ETH",
+            CurrencyType::Token { symbol, .. } => symbol,
+            CurrencyType::Unknown(name) => name,
+        }
+    }
+
+    /// Human-readable name, e.g. "Bitcoin"
+    pub fn display_name(&self) -> &str {
+        match self {
+            CurrencyType::Bitcoin => "Bitcoin",
+            CurrencyType::Ethereum => "Ethereum",
+            CurrencyType::Token { name, .. } => name,
+            CurrencyType::Unknown(name) => name,
+        }
+    }
+
+    /// Number of decimal places in the currency's smallest unit
+    pub fn decimals(&self) -> u32 {
+        match self {
+            CurrencyType::Bitcoin => 8,   // satoshi
+            CurrencyType::Ethereum => 18, // wei
+            CurrencyType::Token { decimals, .. } => *decimals,
+            CurrencyType::Unknown(_) => 0,
+        }
+    }
+
+    /// Stable numeric tag used in binary encodings
+    pub fn tag(&self) -> u8 {
+        match self {
+            CurrencyType::Bitcoin => 0,
+            CurrencyType::Ethereum => 1,
+            CurrencyType::Token { .. } => 2,
+            CurrencyType::Unknown(_) => u8::MAX,
+        }
+    }
+
+    /// Check the currency carries the metadata needed to create a wallet or transact
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            CurrencyType::Token { contract, symbol, .. } => {
+                if contract.trim().is_empty() {
+                    return Err(CryptoNodeError::InvalidInput("Token contract cannot be empty".to_string()));
+                }
+                if symbol.trim().is_empty() {
+                    return Err(CryptoNodeError::InvalidInput("Token symbol cannot be empty".to_string()));
+                }
+            }
+            CurrencyType::Unknown(name) => {
+                return Err(CryptoNodeError::NotImplemented(format!(
+                    "Currency {} is not supported by this version", name
+                )));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Reject amounts with more decimal places than the currency supports
+    pub fn check_precision(&self, amount: f64) -> Result<()> {
+        // Display gives the shortest representation that round-trips, without exponents
+        let formatted = amount.to_string();
+        let places = formatted.split_once('.').map_or(0, |(_, frac)| frac.len());
+        if places > self.decimals() as usize {
+            return Err(CryptoNodeError::InvalidInput(format!(
+                "{} supports at most {} decimal places, got {}",
+                self.display_name(), self.decimals(), formatted
+            )));
+        }
+        Ok(())
+    }
+
+    /// Round an amount down to the currency's precision
+    pub fn truncate(&self, amount: f64) -> f64 {
+        self.round(amount, RoundingMode::Down)
+    }
+
+    /// Round an amount to the currency's precision with the given mode
+    pub fn round(&self, amount: f64, mode: RoundingMode) -> f64 {
+        let scale = 10f64.powi(self.decimals() as i32);
+        let scaled = amount * scale;
+        let rounded = match mode {
+            RoundingMode::HalfUp => scaled.round(),
+            RoundingMode::HalfEven => scaled.round_ties_even(),
+            RoundingMode::Down => scaled.floor(),
+        };
+        rounded / scale
+    }
+
+    /// Format an amount with the currency's precision and symbol, e.g. "0.50000000 BTC"
+    pub fn format_amount(&self, amount: f64) -> String {
+        format!("{:.*} {}", self.decimals() as usize, amount, self.symbol())
+    }
+
+    /// Convert an amount to integer base units of this currency
+    pub fn to_base_units(&self, amount: f64) -> Result<u128> {
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(CryptoNodeError::InvalidInput(format!("Invalid amount: {}", amount)));
+        }
+        let units = (amount * 10f64.powi(self.decimals() as i32)).round();
+        if units > u128::MAX as f64 {
+            return Err(CryptoNodeError::InvalidInput(format!("Amount too large: {}", amount)));
+        }
+        Ok(units as u128)
+    }
+}
+
+/// How amounts are rounded to a currency's precision when finalized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RoundingMode {
+    /// Halves round away from zero
+    HalfUp,
+    /// Halves round to the even neighbour (banker's rounding)
+    #[default]
+    HalfEven,
+    /// Always round towards negative infinity
+    Down,
+}
+
+/// Fail with `InvalidInput` unless a monetary value is finite and non-negative
+fn check_monetary(field: &str, value: f64) -> Result<()> {
+    if !value.is_finite() || value < 0.0 {
+        return Err(CryptoNodeError::InvalidInput(format!("Invalid {}: {}", field, value)));
+    }
+    Ok(())
 }
 
 /// Represents a cryptocurrency transaction
@@ -36,6 +303,75 @@ pub struct Transaction {
     pub timestamp: DateTime<Utc>,
     pub status: TransactionStatus,
     pub fee: Option<f64>,
+    #[serde(default)]
+    pub signature: Option<Vec<u8>>,
+    #[serde(default)]
+    pub category: Option<String>, // Local reporting label, e.g. "income"
+}
+
+impl Transaction {
+    /// Reject a non-finite or negative amount or fee, e.g. from corrupted storage
+    pub fn check_amounts(&self) -> Result<()> {
+        check_monetary("amount", self.amount)?;
+        if let Some(fee) = self.fee {
+            check_monetary("fee", fee)?;
+        }
+        Ok(())
+    }
+
+    /// Canonical byte encoding of the transaction for signing
+    ///
+    /// Fields are written in this fixed order, with all integers big-endian:
+    ///
+    /// 1. `id` as its 16 raw bytes
+    /// 2. `from_wallet` as a u32 byte length followed by its UTF-8 bytes
+    /// 3. `to_wallet` encoded the same way
+    /// 4. `amount` as a u128 count of the currency's base units
+    /// 5. `currency_type` as a single tag byte (see `CurrencyType::tag`),
+    ///    followed for tokens by the length-prefixed contract address
+    /// 6. `timestamp` as i64 microseconds since the Unix epoch
+    /// 7. `fee` as a presence byte (0 or 1), followed by a u128 of base
+    ///    units when present
+    ///
+    /// `status`, `signature` and `category` are excluded so they can change
+    /// without invalidating the signature.
+    pub fn signing_payload(&self) -> Result<Vec<u8>> {
+        self.currency_type.validate()?;
+        let mut payload = Vec::with_capacity(128);
+        payload.extend_from_slice(self.id.as_bytes());
+        write_length_prefixed(&mut payload, self.from_wallet.as_bytes())?;
+        write_length_prefixed(&mut payload, self.to_wallet.as_bytes())?;
+        payload.extend_from_slice(&self.currency_type.to_base_units(self.amount)?.to_be_bytes());
+        payload.push(self.currency_type.tag());
+        if let CurrencyType::Token { contract, .. } = &self.currency_type {
+            write_length_prefixed(&mut payload, contract.as_bytes())?;
+        }
+        payload.extend_from_slice(&self.timestamp.timestamp_micros().to_be_bytes());
+        match self.fee {
+            Some(fee) => {
+                payload.push(1);
+                payload.extend_from_slice(&self.currency_type.to_base_units(fee)?.to_be_bytes());
+            }
+            None => payload.push(0),
+        }
+        Ok(payload)
+    }
+}
+
+/// Append bytes prefixed with their u32 big-endian length
+fn write_length_prefixed(buf: &mut Vec<u8>, bytes: &[u8]) -> Result<()> {
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| CryptoNodeError::Serialization("Field too long to encode".to_string()))?;
+    buf.extend_from_slice(&len.to_be_bytes());
+    buf.extend_from_slice(bytes);
+    Ok(())
+}
+
+/// A transaction awaiting an external signature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    pub transaction: Transaction,
+    pub payload: Vec<u8>,
 }
 
 /// Transaction status
@@ -49,22 +385,165 @@ pub enum TransactionStatus {
 /// Bandwidth sharing metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BandwidthMetrics {
-    pub total_shared: u64,
-    pub current_rate: f64,
-    pub uptime: chrono::Duration,
-    pub rewards: HashMap<CurrencyType, f64>,
+    pub total_shared: u64, // Bytes
+    pub current_rate: f64, // Bytes per second over the last interval
+    #[serde(default)]
+    pub smoothed_rate: f64, // Moving average of current_rate, for projections
+    pub uptime: std::time::Duration, // Time spent measuring, excluding pauses
+    #[serde(with = "currency_amounts")]
+    pub rewards: HashMap<CurrencyType, f64>, // Rewards credited per currency
     pub last_updated: DateTime<Utc>,
+    pub start_time: DateTime<Utc>,
+    pub last_reward: Option<DateTime<Utc>>,
+}
+
+impl BandwidthMetrics {
+    /// Empty metrics starting now
+    pub fn new() -> Self {
+        let now = Utc::now();
+        Self {
+            total_shared: 0,
+            current_rate: 0.0,
+            smoothed_rate: 0.0,
+            uptime: std::time::Duration::ZERO,
+            rewards: HashMap::new(),
+            last_updated: now,
+            start_time: now,
+            last_reward: None,
+        }
+    }
+}
+
+impl Default for BandwidthMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Serializes a per-currency map as a list of pairs
+///
+/// Token currencies serialize as objects, which JSON doesn't allow as map keys.
+mod currency_amounts {
+    use super::CurrencyType;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+
+    pub fn serialize<S: Serializer>(map: &HashMap<CurrencyType, f64>, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        map.iter().collect::<Vec<_>>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<HashMap<CurrencyType, f64>, D::Error> {
+        Ok(Vec::<(CurrencyType, f64)>::deserialize(deserializer)?.into_iter().collect())
+    }
 }
 
 /// Device configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct DeviceConfig {
     pub device_id: Uuid,
+    #[serde(default = "default_device_name")]
+    pub device_name: String,
+    #[serde(default = "default_true")]
+    pub bluetooth_enabled: bool,
     pub bluetooth_name: String,
+    #[serde(default = "default_min_bandwidth")]
+    pub min_bandwidth: u64, // Bytes per measurement interval required to earn rewards
     pub max_bandwidth: u64,
+    #[serde(default = "default_reward_rate")]
+    pub reward_rate: f64, // Reward per MB shared
     pub min_reward_rate: f64,
+    #[serde(default = "default_measurement_interval")]
+    pub measurement_interval: u64, // Seconds between bandwidth measurements
     pub supported_currencies: Vec<CurrencyType>,
-    pub auto_update: bool,
+    #[serde(alias = "auto_update")]
+    pub auto_update_enabled: bool,
+    #[serde(default = "default_update_check_interval")]
+    pub update_check_interval: u64, // Seconds between update checks
+    #[serde(default)]
+    pub auto_backup: AutoBackupConfig,
+    #[serde(default)]
+    pub safe_mode: bool, // Block all outbound transactions
+    #[serde(default)]
+    pub startup_selftest: bool, // Run `selftest` before starting and abort on critical failures
+}
+
+/// Defaults for a freshly provisioned device
+///
+/// Each default config gets a new random `device_id`. Bluetooth is on and
+/// advertises as "CryptoNode", sharing is capped at 100 MiB per interval with
+/// the same 1 MiB floor, reward rate and one-minute measurement interval
+/// `BandwidthManager` uses, and updates are checked daily. No currencies are
+/// listed, which `validate_config` accepts; wallets are created on demand.
+impl Default for DeviceConfig {
+    fn default() -> Self {
+        Self {
+            device_id: Uuid::new_v4(),
+            device_name: default_device_name(),
+            bluetooth_enabled: true,
+            bluetooth_name: "CryptoNode".to_string(),
+            min_bandwidth: default_min_bandwidth(),
+            max_bandwidth: 100 * 1024 * 1024,
+            reward_rate: default_reward_rate(),
+            min_reward_rate: 0.0,
+            measurement_interval: default_measurement_interval(),
+            supported_currencies: Vec::new(),
+            auto_update_enabled: true,
+            update_check_interval: default_update_check_interval(),
+            auto_backup: AutoBackupConfig::default(),
+            safe_mode: false,
+            startup_selftest: false,
+        }
+    }
+}
+
+fn default_device_name() -> String {
+    "CryptoNode".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_min_bandwidth() -> u64 {
+    1024 * 1024
+}
+
+fn default_reward_rate() -> f64 {
+    0.0001
+}
+
+fn default_measurement_interval() -> u64 {
+    60
+}
+
+fn default_update_check_interval() -> u64 {
+    24 * 60 * 60
+}
+
+/// Automatic encrypted wallet backup settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AutoBackupConfig {
+    pub enabled: bool,
+    pub path: PathBuf, // Also where the node loads and saves wallets; relative to the config directory
+    pub passphrase_source: PassphraseSource,
+    pub debounce_secs: u64, // Quiet period before a burst of changes is backed up
+    pub max_batch: usize, // Back up once this many changes are pending, even without a quiet period
+    pub max_delay_secs: u64, // Longest a change may wait before it is backed up
+}
+
+impl Default for AutoBackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: PathBuf::from("wallets.backup"),
+            passphrase_source: PassphraseSource::default(),
+            debounce_secs: 5,
+            max_batch: 100,
+            max_delay_secs: 60,
+        }
+    }
 }
 
 /// Bluetooth connection status
@@ -105,6 +584,34 @@ pub struct DeviceStatus {
     pub last_sync: DateTime<Utc>,
 }
 
+/// One page of a list result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub has_more: bool,
+}
+
+impl<T: Clone> Page<T> {
+    /// Take the page starting at `offset` with at most `limit` items
+    ///
+    /// An offset past the end yields an empty page.
+    pub fn from_slice(items: &[T], offset: usize, limit: usize) -> Self {
+        let total = items.len();
+        let start = offset.min(total);
+        let end = start.saturating_add(limit).min(total);
+        Self {
+            items: items[start..end].to_vec(),
+            total,
+            offset,
+            limit,
+            has_more: end < total,
+        }
+    }
+}
+
 /// API Response wrapper
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ApiResponse<T> {
@@ -112,4 +619,195 @@ pub struct ApiResponse<T> {
     pub data: Option<T>,
     pub error: Option<String>,
     pub timestamp: DateTime<Utc>,
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_backup_rejects_unknown_fields() {
+        let parsed: std::result::Result<AutoBackupConfig, _> = serde_json::from_str(r#"{"enabeld": true}"#);
+        assert!(parsed.is_err());
+    }
+
+    #[test]
+    fn auto_backup_fills_missing_fields_with_defaults() {
+        let parsed: AutoBackupConfig = serde_json::from_str(r#"{"enabled": true}"#).unwrap();
+        assert!(parsed.enabled);
+        assert_eq!(parsed.path, AutoBackupConfig::default().path);
+    }
+
+    /// A transaction with fixed values, for byte-exact payload vectors
+    fn vector_transaction(currency_type: CurrencyType, amount: f64, fee: Option<f64>) -> Transaction {
+        Transaction {
+            id: Uuid::from_u128(0x0011_2233_4455_6677_8899_aabb_ccdd_eeff),
+            from_wallet: "ab".to_string(),
+            to_wallet: "cd".to_string(),
+            amount,
+            currency_type,
+            timestamp: "2024-01-01T00:00:00Z".parse().unwrap(),
+            status: TransactionStatus::Pending,
+            fee,
+            signature: None,
+            category: None,
+        }
+    }
+
+    #[test]
+    fn signing_payload_matches_bitcoin_vector() {
+        let tx = vector_transaction(CurrencyType::Bitcoin, 1.5, Some(0.0001));
+        let expected = concat!(
+            "00112233445566778899aabbccddeeff",
+            "00000002", "6162",
+            "00000002", "6364",
+            "00000000000000000000000008f0d180",
+            "00",
+            "00060dd710212000",
+            "01", "00000000000000000000000000002710",
+        );
+        assert_eq!(hex::encode(tx.signing_payload().unwrap()), expected);
+    }
+
+    #[test]
+    fn signing_payload_matches_token_vector() {
+        let token = CurrencyType::Token {
+            contract: "0xabc".to_string(),
+            symbol: "TOK".to_string(),
+            name: "Token".to_string(),
+            decimals: 6,
+        };
+        let tx = vector_transaction(token, 2.5, None);
+        let expected = concat!(
+            "00112233445566778899aabbccddeeff",
+            "00000002", "6162",
+            "00000002", "6364",
+            "000000000000000000000000002625a0",
+            "02", "00000005", "3078616263",
+            "00060dd710212000",
+            "00",
+        );
+        assert_eq!(hex::encode(tx.signing_payload().unwrap()), expected);
+    }
+
+    #[test]
+    fn signing_payload_ignores_status_signature_and_category() {
+        let tx = vector_transaction(CurrencyType::Bitcoin, 1.5, None);
+        let changed = Transaction {
+            status: TransactionStatus::Confirmed,
+            signature: Some(vec![1, 2, 3]),
+            category: Some("rent".to_string()),
+            ..tx.clone()
+        };
+        assert_eq!(tx.signing_payload().unwrap(), changed.signing_payload().unwrap());
+        let other_amount = Transaction { amount: 1.50000001, ..tx.clone() };
+        assert_ne!(tx.signing_payload().unwrap(), other_amount.signing_payload().unwrap());
+    }
+
+    #[test]
+    fn currencies_report_symbol_name_and_precision() {
+        assert_eq!(CurrencyType::Bitcoin.symbol(), "BTC");
+        assert_eq!(CurrencyType::Bitcoin.display_name(), "Bitcoin");
+        assert_eq!(CurrencyType::Ethereum.symbol(), "ETH");
+        assert_eq!(CurrencyType::Ethereum.decimals(), 18);
+
+        let usdc = CurrencyType::Token {
+            contract: "0xa0b8".to_string(),
+            symbol: "USDC".to_string(),
+            name: "USD Coin".to_string(),
+            decimals: 6,
+        };
+        assert_eq!(usdc.symbol(), "USDC");
+        assert_eq!(usdc.display_name(), "USD Coin");
+        assert_eq!(usdc.format_amount(1.5), "1.500000 USDC");
+        assert_eq!(CurrencyType::Bitcoin.format_amount(0.5), "0.50000000 BTC");
+    }
+
+    #[test]
+    fn precision_allows_up_to_the_currency_decimals() {
+        assert!(CurrencyType::Bitcoin.check_precision(0.00000001).is_ok());
+        assert!(CurrencyType::Bitcoin.check_precision(2.0).is_ok());
+        assert!(matches!(
+            CurrencyType::Bitcoin.check_precision(0.000000001),
+            Err(CryptoNodeError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn page_reports_totals_and_remaining_items() {
+        let items: Vec<u32> = (0..5).collect();
+
+        let first = Page::from_slice(&items, 0, 2);
+        assert_eq!(first.items, vec![0, 1]);
+        assert_eq!(first.total, 5);
+        assert!(first.has_more);
+
+        let last = Page::from_slice(&items, 4, 2);
+        assert_eq!(last.items, vec![4]);
+        assert!(!last.has_more);
+
+        let past_end = Page::from_slice(&items, 9, usize::MAX);
+        assert!(past_end.items.is_empty());
+        assert_eq!(past_end.offset, 9);
+        assert!(!past_end.has_more);
+    }
+
+    #[test]
+    fn unrecognized_currencies_load_as_unknown_and_round_trip() {
+        let currencies: Vec<CurrencyType> = serde_json::from_str(
+            r#"["Solana", {"Cardano": {"era": "shelley"}}, "Bitcoin", {"Token": {"contract": "0x01", "symbol": "TOK", "name": "Token", "decimals": 6}}]"#,
+        ).unwrap();
+
+        assert_eq!(currencies[0], CurrencyType::Unknown("Solana".to_string()));
+        assert_eq!(currencies[1], CurrencyType::Unknown("Cardano".to_string()));
+        assert_eq!(currencies[2], CurrencyType::Bitcoin);
+        assert!(matches!(&currencies[3], CurrencyType::Token { decimals: 6, .. }));
+        assert_eq!(serde_json::to_string(&currencies[0]).unwrap(), r#""Solana""#);
+        assert!(matches!(currencies[0].validate(), Err(CryptoNodeError::NotImplemented(_))));
+    }
+
+    #[test]
+    fn malformed_known_currencies_still_fail_to_load() {
+        assert!(serde_json::from_str::<CurrencyType>(r#"{"Token": {"symbol": "TOK"}}"#).is_err());
+        assert!(serde_json::from_str::<CurrencyType>("42").is_err());
+    }
+
+    #[test]
+    fn transaction_amounts_must_be_finite_and_non_negative() {
+        assert!(vector_transaction(CurrencyType::Bitcoin, 1.5, Some(0.0001)).check_amounts().is_ok());
+        for (amount, fee) in [(f64::NAN, None), (-1.0, None), (f64::INFINITY, None), (1.0, Some(f64::NAN)), (1.0, Some(-0.1))] {
+            let result = vector_transaction(CurrencyType::Bitcoin, amount, fee).check_amounts();
+            assert!(matches!(result, Err(CryptoNodeError::InvalidInput(_))), "{} {:?}", amount, fee);
+        }
+    }
+
+    #[test]
+    fn bandwidth_metrics_round_trip_with_token_rewards() {
+        let token = CurrencyType::Token {
+            contract: "0xabc".to_string(),
+            symbol: "TOK".to_string(),
+            name: "Token".to_string(),
+            decimals: 6,
+        };
+        let mut metrics = BandwidthMetrics::new();
+        metrics.total_shared = 4096;
+        metrics.uptime = std::time::Duration::from_secs(90);
+        metrics.rewards = HashMap::from([(CurrencyType::Bitcoin, 0.5), (token.clone(), 2.0)]);
+
+        let json = serde_json::to_string(&metrics).unwrap();
+        let loaded: BandwidthMetrics = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.total_shared, 4096);
+        assert_eq!(loaded.uptime, metrics.uptime);
+        assert_eq!(loaded.rewards, metrics.rewards);
+        assert_eq!(loaded.rewards[&token], 2.0);
+    }
+
+    #[test]
+    fn default_device_configs_get_fresh_ids_and_shared_defaults() {
+        let (first, second) = (DeviceConfig::default(), DeviceConfig::default());
+        assert_ne!(first.device_id, second.device_id);
+        assert_eq!(first.bluetooth_name, "CryptoNode");
+        assert_eq!(first.min_bandwidth, default_min_bandwidth());
+        assert_eq!(first.reward_rate, default_reward_rate());
+        assert!(first.supported_currencies.is_empty());
+    }
+}
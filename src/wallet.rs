@@ -1,21 +1,429 @@
 use crate::{
     Result,
     error::CryptoNodeError,
-    types::{Wallet, Transaction, CurrencyType, TransactionStatus},
+    crypto::{self, SigningAlgorithm},
+    policy,
+    tasks,
+    storage::{self, PassphraseSource, WalletBackup},
+    types::{AutoBackupConfig, BalanceView, DeviceConfig, Page, RoundingMode, Wallet, WalletView, Transaction, UnsignedTransaction, CurrencyType, TransactionStatus},
 };
-use ed25519_dalek::{Keypair, SecretKey, PublicKey};
 use ring::rand::SystemRandom;
 use uuid::Uuid;
-use chrono::Utc;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tokio::sync::{RwLock, broadcast, mpsc, oneshot, watch};
+use tokio::time::{Duration, Instant, timeout};
+use tracing::{info, warn};
+
+/// Events emitted by the wallet manager
+#[derive(Debug, Clone)]
+pub enum WalletEvent {
+    /// A transaction was sent to an address this node has already transacted with
+    AddressReuseWarning { address: String, transaction_id: Uuid },
+    /// A wallet's balance changed
+    BalanceChanged {
+        wallet_id: Uuid,
+        old_balance: f64,
+        new_balance: f64,
+        reason: BalanceChangeReason,
+    },
+    /// A wallet was deleted
+    WalletDeleted { wallet_id: Uuid },
+}
+
+/// Why a wallet balance changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceChangeReason {
+    /// Bandwidth sharing reward
+    Reward,
+    /// A transaction confirmed
+    Transaction,
+    /// Synchronized from an external source
+    ExternalSync,
+    /// Set directly by a caller
+    Manual,
+}
+
+/// Window over which transaction throughput is measured
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Transaction creation statistics
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransactionMetrics {
+    pub total_created: u64,
+    pub transactions_per_sec: f64, // Averaged over the last minute
+    pub stored: usize,
+}
+
+/// Fee attached to new transactions
+const ESTIMATED_FEE: f64 = 0.001; // Example fee, should be calculated based on network conditions
+
+/// Default sanity cap on any wallet balance
+///
+/// Above any real supply, and well below where `f64` loses sub-unit precision.
+pub const DEFAULT_MAX_BALANCE: f64 = 1e15;
+
+/// Most queued transactions stored under one lock acquisition
+const QUEUE_BATCH_SIZE: usize = 64;
+
+/// A validated transaction waiting for the queue worker to store it
+struct QueuedTransaction {
+    transaction: Transaction,
+    fee_model: FeeModel, // Of the transaction's currency
+    reply: oneshot::Sender<Result<bool>>, // Whether the recipient address was already in use
+}
+
+/// Message to the auto-backup task
+enum BackupSignal {
+    /// Wallet state changed
+    Changed,
+    /// Write a backup immediately and report the outcome
+    FlushNow(oneshot::Sender<Result<()>>),
+}
+
+/// Handle to the running auto-backup task
+#[derive(Clone)]
+struct BackupTrigger {
+    path: PathBuf, // The file only this task writes
+    signals: mpsc::Sender<BackupSignal>,
+}
+
+/// Transaction log with secondary indexes by id and address
+///
+/// Both live under the same lock so the index never disagrees with the log.
+#[derive(Default)]
+struct TransactionStore {
+    transactions: Vec<Transaction>,
+    by_id: HashMap<Uuid, usize>,
+    by_address: HashMap<String, Vec<usize>>,
+    max_transactions: Option<usize>, // Prune old failed transactions beyond this
+    total_created: u64,
+    recent: VecDeque<Instant>,
+}
+
+impl TransactionStore {
+    /// Append a transaction and index its id and both of its addresses
+    fn push(&mut self, transaction: Transaction) {
+        let position = self.transactions.len();
+        self.index(position, &transaction);
+        self.transactions.push(transaction);
+    }
+
+    fn index(&mut self, position: usize, transaction: &Transaction) {
+        self.by_id.insert(transaction.id, position);
+        self.by_address.entry(normalize_address(&transaction.from_wallet)).or_default().push(position);
+        if !same_address(&transaction.to_wallet, &transaction.from_wallet) {
+            self.by_address.entry(normalize_address(&transaction.to_wallet)).or_default().push(position);
+        }
+    }
+
+    /// Replace the log, rebuilding the index
+    fn replace(&mut self, transactions: Vec<Transaction>) {
+        self.transactions = transactions;
+        self.rebuild_index();
+    }
+
+    fn rebuild_index(&mut self) {
+        self.by_id.clear();
+        self.by_address.clear();
+        let transactions = std::mem::take(&mut self.transactions);
+        for (position, transaction) in transactions.iter().enumerate() {
+            self.index(position, transaction);
+        }
+        self.transactions = transactions;
+    }
+
+    /// Append a newly created transaction, updating throughput and enforcing the cap
+    fn record(&mut self, transaction: Transaction) {
+        self.push(transaction);
+        self.total_created += 1;
+
+        let now = Instant::now();
+        self.recent.push_back(now);
+        while self.recent.front().is_some_and(|t| now.duration_since(*t) > THROUGHPUT_WINDOW) {
+            self.recent.pop_front();
+        }
+
+        self.enforce_cap();
+    }
+
+    /// Drop the oldest failed transactions until the store fits the cap
+    ///
+    /// Pending and confirmed transactions are never pruned. Without failed
+    /// entries to drop the store is left as is and grows past the cap; the
+    /// index is only rebuilt when something was actually removed.
+    fn enforce_cap(&mut self) {
+        let Some(max) = self.max_transactions else {
+            return;
+        };
+        let mut excess = self.transactions.len().saturating_sub(max);
+        if excess == 0 {
+            return;
+        }
+
+        let before = self.transactions.len();
+        self.transactions.retain(|t| {
+            if excess > 0 && t.status == TransactionStatus::Failed {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+        if self.transactions.len() != before {
+            self.rebuild_index();
+        }
+    }
+
+    fn metrics(&self) -> TransactionMetrics {
+        let now = Instant::now();
+        let recent = self.recent.iter()
+            .filter(|t| now.duration_since(**t) <= THROUGHPUT_WINDOW)
+            .count();
+        TransactionMetrics {
+            total_created: self.total_created,
+            transactions_per_sec: recent as f64 / THROUGHPUT_WINDOW.as_secs_f64(),
+            stored: self.transactions.len(),
+        }
+    }
+
+    fn get(&self, id: Uuid) -> Option<&Transaction> {
+        self.by_id.get(&id).map(|&i| &self.transactions[i])
+    }
+
+    fn get_mut(&mut self, id: Uuid) -> Option<&mut Transaction> {
+        self.by_id.get(&id).map(|&i| &mut self.transactions[i])
+    }
+
+    /// Validate a new outgoing transaction and record it
+    ///
+    /// Checks run under the store lock, so the sender's balance and pending
+    /// debits can't change between the check and the insert. Returns whether
+    /// the recipient address was already in use.
+    fn admit(&mut self, wallets: &HashMap<Uuid, Wallet>, fee_model: FeeModel, transaction: Transaction) -> Result<bool> {
+        if self.get(transaction.id).is_some() {
+            return Err(CryptoNodeError::InvalidInput(format!("Transaction {} already exists", transaction.id)));
+        }
+        let sender = check_outgoing(wallets, &transaction)?;
+        let spendable = sender.balance - self.pending_debits(&sender.address, fee_model);
+        check_spendable(spendable, transaction.amount, transaction.fee.unwrap_or(0.0), fee_model)?;
+        let reused = self.contains_address(&transaction.to_wallet);
+        self.record(transaction);
+        Ok(reused)
+    }
+
+    /// What confirming an address's pending outgoing transactions would debit
+    fn pending_debits(&self, address: &str, fee_model: FeeModel) -> f64 {
+        self.history(address).iter()
+            .filter(|t| t.status == TransactionStatus::Pending && same_address(&t.from_wallet, address))
+            .map(|t| fee_model.settle(t.amount, t.fee.unwrap_or(0.0)).0)
+            .sum()
+    }
+
+    /// Whether any stored transaction involves an address
+    fn contains_address(&self, address: &str) -> bool {
+        self.by_address.get(&normalize_address(address)).is_some_and(|positions| !positions.is_empty())
+    }
+
+    /// Transactions involving a wallet's address or its receive addresses, in insertion order
+    fn wallet_history(&self, wallet: &Wallet) -> Vec<Transaction> {
+        let mut positions: Vec<usize> = std::iter::once(&wallet.address)
+            .chain(&wallet.receive_addresses)
+            .filter_map(|address| self.by_address.get(&normalize_address(address)))
+            .flatten()
+            .copied()
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions.into_iter().map(|i| self.transactions[i].clone()).collect()
+    }
+
+    /// Transactions sending from or to an address, in insertion order
+    fn history(&self, address: &str) -> Vec<Transaction> {
+        self.by_address.get(&normalize_address(address))
+            .map(|positions| positions.iter().map(|&i| self.transactions[i].clone()).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Policy hook consulted before a transaction is recorded
+pub trait TransactionApprover: Send + Sync {
+    /// Return `Ok(true)` to allow the transaction, `Ok(false)` to deny it
+    fn approve(&self, tx: &Transaction) -> Result<bool>;
+}
+
+/// Approver that allows every transaction
+pub struct AutoApprove;
+
+impl TransactionApprover for AutoApprove {
+    fn approve(&self, _tx: &Transaction) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+/// Converts value between currencies for `transfer_between_currencies`
+pub trait ExchangeBackend: Send + Sync {
+    /// Address source funds are sent to and proceeds are received from
+    ///
+    /// Like wallet addresses, the hex-encoded public key behind `sign`.
+    fn address(&self) -> String;
+
+    /// Amount `execute` is expected to return for `amount`, without executing
+    fn quote(&self, from: &CurrencyType, to: &CurrencyType, amount: f64) -> Result<f64>;
+
+    /// Execute the exchange and return the amount received in `to`
+    fn execute(&self, from: &CurrencyType, to: &CurrencyType, amount: f64) -> Result<f64>;
+
+    /// Sign a transaction payload paying out exchange proceeds
+    fn sign(&self, payload: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Estimates how long a pending transaction will take to confirm
+pub trait ConfirmationEstimator: Send + Sync {
+    /// Return `None` when no estimate is available
+    fn estimate(&self, tx: &Transaction) -> Result<Option<Duration>>;
+}
+
+/// Why a wallet needs attention
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttentionReason {
+    /// A pending transaction has been waiting longer than expected
+    StalePendingTransaction,
+    /// Balance is below the low-water mark
+    LowBalance,
+    /// The wallet hasn't been updated in a long time
+    StaleWallet,
+    /// A transaction failed recently
+    RecentFailure,
+}
+
+/// A wallet flagged for the dashboard's action-required view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttentionItem {
+    pub wallet_id: Uuid,
+    pub reason: AttentionReason,
+}
+
+/// Overview of a wallet for account screens
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalletSummary {
+    pub wallet_id: Uuid,
+    pub age: chrono::Duration,
+    pub last_activity: Option<DateTime<Utc>>, // Timestamp of the most recent transaction
+    pub pending: usize,
+    pub confirmed: usize,
+    pub failed: usize,
+    pub balance: f64,
+}
+
+/// Limits used by `wallets_needing_attention`
+#[derive(Debug, Clone, Copy)]
+pub struct AttentionThresholds {
+    pub pending_age: chrono::Duration,
+    pub low_balance: f64,
+    pub stale_after: chrono::Duration,
+    pub failure_window: chrono::Duration,
+}
+
+impl Default for AttentionThresholds {
+    fn default() -> Self {
+        Self {
+            pending_age: chrono::Duration::hours(1),
+            low_balance: 0.0, // Only flags negative balances
+            stale_after: chrono::Duration::days(30),
+            failure_window: chrono::Duration::days(1),
+        }
+    }
+}
+
+/// How a transaction fee is applied when it confirms
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FeeModel {
+    /// Sender pays `amount + fee`, recipient receives `amount`
+    #[default]
+    AddedToAmount,
+    /// Sender pays `amount`, recipient receives `amount - fee`
+    DeductedFromAmount,
+}
+
+impl FeeModel {
+    /// Sender debit and recipient credit for a confirmed transaction
+    pub fn settle(&self, amount: f64, fee: f64) -> (f64, f64) {
+        match self {
+            FeeModel::AddedToAmount => (amount + fee, amount),
+            FeeModel::DeductedFromAmount => (amount, (amount - fee).max(0.0)),
+        }
+    }
+}
+
+/// Result of `create_wallet_detailed`
+#[derive(Debug, Clone)]
+pub struct WalletCreation {
+    pub wallet: WalletView,
+    pub mnemonic: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// A status disagreement found while merging transaction histories
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub transaction_id: Uuid,
+    pub local: TransactionStatus,
+    pub remote: TransactionStatus,
+    pub resolved: TransactionStatus,
+}
+
+/// Outcome of merging another node's transaction history
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    pub added: usize,
+    pub unchanged: usize,
+    pub conflicts: Vec<MergeConflict>,
+    pub rejected: Vec<Uuid>, // Status changes `update_transaction_status` refused
+}
+
+/// Where `persist` writes wallet state
+struct DataFile {
+    path: PathBuf,
+    passphrase_source: PassphraseSource,
+}
+
+/// Point-in-time copy of all wallet and transaction state
+#[derive(Debug, Clone)]
+pub struct WalletSnapshot {
+    pub wallets: HashMap<Uuid, Wallet>,
+    pub transactions: Vec<Transaction>,
+    /// Private keys were stripped; such a snapshot cannot be restored
+    pub redacted: bool,
+}
 
 /// Manages cryptocurrency wallets and transactions
 pub struct WalletManager {
     wallets: Arc<RwLock<HashMap<Uuid, Wallet>>>,
-    transactions: Arc<RwLock<Vec<Transaction>>>,
-    rng: SystemRandom,
+    transactions: Arc<RwLock<TransactionStore>>,
+    rng: RwLock<Arc<dyn crypto::EntropySource>>,
+    backup_trigger: RwLock<Option<BackupTrigger>>,
+    pending_changes: Arc<AtomicUsize>,
+    event_sender: broadcast::Sender<WalletEvent>,
+    approver: RwLock<Arc<dyn TransactionApprover>>,
+    confirmation_estimator: RwLock<Option<Arc<dyn ConfirmationEstimator>>>,
+    safe_mode: Arc<AtomicBool>,
+    attention_thresholds: RwLock<AttentionThresholds>,
+    fee_models: RwLock<HashMap<CurrencyType, FeeModel>>,
+    transaction_queue: RwLock<Option<mpsc::Sender<QueuedTransaction>>>,
+    max_balance: RwLock<Option<f64>>,
+    shut_down: AtomicBool, // Set by `shutdown`; later mutations fail with `Cancelled`
+    rounding_mode: std::sync::Mutex<RoundingMode>, // Applied to estimated fees and reward payouts
+    data_file: RwLock<Option<DataFile>>, // Set by `load_from_disk`
+}
+
+impl Default for WalletManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl WalletManager {
@@ -23,41 +431,266 @@ impl WalletManager {
     pub fn new() -> Self {
         Self {
             wallets: Arc::new(RwLock::new(HashMap::new())),
-            transactions: Arc::new(RwLock::new(Vec::new())),
-            rng: SystemRandom::new(),
+            transactions: Arc::new(RwLock::new(TransactionStore::default())),
+            rng: RwLock::new(Arc::new(SystemRandom::new())),
+            backup_trigger: RwLock::new(None),
+            pending_changes: Arc::new(AtomicUsize::new(0)),
+            event_sender: broadcast::channel(100).0,
+            approver: RwLock::new(Arc::new(AutoApprove)),
+            confirmation_estimator: RwLock::new(None),
+            safe_mode: Arc::new(AtomicBool::new(false)),
+            attention_thresholds: RwLock::new(AttentionThresholds::default()),
+            fee_models: RwLock::new(HashMap::new()),
+            transaction_queue: RwLock::new(None),
+            max_balance: RwLock::new(Some(DEFAULT_MAX_BALANCE)),
+            shut_down: AtomicBool::new(false),
+            rounding_mode: std::sync::Mutex::new(RoundingMode::default()),
+            data_file: RwLock::new(None),
+        }
+    }
+
+    /// Create a wallet manager backed by the file at `path`
+    ///
+    /// Wallets and transactions saved there by `persist` are loaded; a missing
+    /// file starts empty. The file uses the encrypted backup format, so private
+    /// keys are never written in plaintext.
+    pub async fn load_from_disk(path: &Path, passphrase_source: PassphraseSource) -> Result<Self> {
+        // Resolve up front so a missing passphrase fails now, not at the first persist
+        let passphrase = passphrase_source.resolve()?;
+        let manager = Self::new();
+        if path.exists() {
+            let data = storage::restore_backup(path, &passphrase)?;
+            manager.restore(WalletSnapshot {
+                wallets: data.wallets.into_iter().map(|wallet| (wallet.id, wallet)).collect(),
+                transactions: data.transactions,
+                redacted: false,
+            }).await?;
+            info!("Loaded wallets from {}", path.display());
+        }
+
+        *manager.data_file.write().await = Some(DataFile {
+            path: path.to_path_buf(),
+            passphrase_source,
+        });
+        Ok(manager)
+    }
+
+    /// Write all wallets and transactions to the file given to `load_from_disk`
+    ///
+    /// If the auto-backup task writes the same file, the write is handed to
+    /// it, so two saves never overlap on one file.
+    pub async fn persist(&self) -> Result<()> {
+        let data_file = self.data_file.read().await;
+        let data_file = data_file.as_ref()
+            .ok_or_else(|| CryptoNodeError::Storage("Wallet manager has no data file".to_string()))?;
+        let trigger = self.backup_trigger.read().await.clone();
+        if let Some(trigger) = trigger.filter(|trigger| trigger.path == data_file.path) {
+            return flush_via(&trigger.signals).await;
+        }
+        write_backup(&data_file.path, &data_file.passphrase_source, &self.wallets, &self.transactions).await
+    }
+
+    /// Enable or disable safe mode
+    ///
+    /// While active, no outbound transactions can be created. Wallet creation
+    /// and balance tracking are unaffected.
+    pub fn set_safe_mode(&self, enabled: bool) {
+        self.safe_mode.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Whether safe mode is active
+    pub fn is_safe_mode(&self) -> bool {
+        self.safe_mode.load(Ordering::SeqCst)
+    }
+
+    /// Keep runtime settings in sync with config changes
+    pub fn follow_config(&self, mut config: watch::Receiver<DeviceConfig>) {
+        let safe_mode = self.safe_mode.clone();
+        tasks::spawn("wallet-config-watcher", async move {
+            loop {
+                safe_mode.store(config.borrow_and_update().safe_mode, Ordering::SeqCst);
+                if config.changed().await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Reject mutations once `shutdown` has been called
+    fn ensure_running(&self) -> Result<()> {
+        if self.shut_down.load(Ordering::SeqCst) {
+            return Err(CryptoNodeError::Cancelled);
+        }
+        Ok(())
+    }
+
+    fn ensure_not_safe_mode(&self) -> Result<()> {
+        if self.is_safe_mode() {
+            return Err(CryptoNodeError::PermissionDenied("safe mode active".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Set the approver consulted before transactions are recorded
+    pub async fn set_approver(&self, approver: Arc<dyn TransactionApprover>) {
+        *self.approver.write().await = approver;
+    }
+
+    /// Ask the configured approver whether a transaction may proceed
+    async fn check_approval(&self, transaction: &Transaction) -> Result<()> {
+        let approver = self.approver.read().await.clone();
+        if !approver.approve(transaction)? {
+            return Err(CryptoNodeError::PermissionDenied(format!("Transaction {} was not approved", transaction.id)));
         }
+        Ok(())
+    }
+
+    /// Subscribe to wallet events
+    pub fn subscribe(&self) -> broadcast::Receiver<WalletEvent> {
+        self.event_sender.subscribe()
     }
 
     /// Create a new wallet for a specific cryptocurrency
+    ///
+    /// The signing algorithm follows the currency; see `SigningAlgorithm::for_currency`.
     pub async fn create_wallet(&self, currency_type: CurrencyType) -> Result<Wallet> {
-        // Generate key pair
-        let secret_key_bytes = {
-            let mut bytes = [0u8; 32];
-            ring::rand::SecureRandom::fill(&self.rng, &mut bytes)
+        let algorithm = SigningAlgorithm::for_currency(&currency_type);
+        self.create_wallet_with_algorithm(currency_type, algorithm).await
+    }
+
+    /// Create a new wallet with an explicit signing algorithm
+    pub async fn create_wallet_with_algorithm(
+        &self,
+        currency_type: CurrencyType,
+        algorithm: SigningAlgorithm,
+    ) -> Result<Wallet> {
+        self.ensure_running()?;
+        let secret_key_bytes = self.generate_secret_key().await?;
+        self.store_new_wallet(currency_type, algorithm, secret_key_bytes).await
+    }
+
+    /// Create a wallet and report the recovery phrase and any advisories
+    ///
+    /// When `with_mnemonic` is set, the BIP-39 phrase encoding the wallet's
+    /// secret key is returned; it is the only copy and is not stored.
+    pub async fn create_wallet_detailed(
+        &self,
+        currency_type: CurrencyType,
+        with_mnemonic: bool,
+    ) -> Result<WalletCreation> {
+        self.ensure_running()?;
+        let secret_key_bytes = self.generate_secret_key().await?;
+        let algorithm = SigningAlgorithm::for_currency(&currency_type);
+        let wallet = self.store_new_wallet(currency_type, algorithm, secret_key_bytes).await?;
+
+        let mnemonic = if with_mnemonic {
+            let phrase = bip39::Mnemonic::from_entropy(&secret_key_bytes)
                 .map_err(|e| CryptoNodeError::CryptoOperation(e.to_string()))?;
-            bytes
+            Some(phrase.to_string())
+        } else {
+            None
         };
 
-        let secret_key = SecretKey::from_bytes(&secret_key_bytes)
-            .map_err(|e| CryptoNodeError::CryptoOperation(e.to_string()))?;
-        let public_key = PublicKey::from(&secret_key);
-        let keypair = Keypair { secret: secret_key, public: public_key };
+        let mut warnings = Vec::new();
+        if mnemonic.is_none() {
+            warnings.push("No recovery phrase was generated; the wallet cannot be recovered if this device is lost".to_string());
+        }
+        if self.backup_trigger.read().await.is_none() {
+            warnings.push("Automatic backups are disabled".to_string());
+        }
+        if self.is_safe_mode() {
+            warnings.push("Safe mode is active; the wallet cannot send transactions".to_string());
+        }
+
+        Ok(WalletCreation {
+            wallet: WalletView::from(&wallet),
+            mnemonic,
+            warnings,
+        })
+    }
 
-        // Create wallet with generated keys
-        let wallet = Wallet {
-            id: Uuid::new_v4(),
-            address: hex::encode(keypair.public.as_bytes()),
-            public_key: keypair.public.as_bytes().to_vec(),
-            private_key: keypair.secret.as_bytes().to_vec(),
-            currency_type,
-            balance: 0.0,
-            created_at: Utc::now(),
-            last_updated: Utc::now(),
-        };
+    /// Import a wallet from an existing secret key
+    ///
+    /// The address is derived with `algorithm`, so it must be the algorithm
+    /// the key was used with; the same key yields a different address under
+    /// the other one. It is recorded on the wallet.
+    pub async fn import_wallet(
+        &self,
+        currency_type: CurrencyType,
+        algorithm: SigningAlgorithm,
+        secret_key: &[u8],
+    ) -> Result<Wallet> {
+        self.ensure_running()?;
+        algorithm.validate_secret_key(secret_key)?;
+        let secret_key_bytes: [u8; 32] = secret_key.try_into()
+            .map_err(|_| CryptoNodeError::InvalidInput("Secret key must be 32 bytes".to_string()))?;
+        self.store_new_wallet(currency_type, algorithm, secret_key_bytes).await
+    }
+
+    /// Replace the entropy source used for key generation
+    pub async fn set_entropy_source(&self, source: Arc<dyn crypto::EntropySource>) {
+        *self.rng.write().await = source;
+    }
+
+    /// Generate a fresh random 32-byte secret key for either signing algorithm
+    ///
+    /// The entropy source is health-checked first so an unseeded RNG can't
+    /// produce weak keys.
+    async fn generate_secret_key(&self) -> Result<[u8; 32]> {
+        let rng = self.rng.read().await.clone();
+        crypto::check_entropy(rng.as_ref())?;
+
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Build a wallet from a secret key and store it
+    async fn store_new_wallet(
+        &self,
+        currency_type: CurrencyType,
+        algorithm: SigningAlgorithm,
+        secret_key_bytes: [u8; 32],
+    ) -> Result<Wallet> {
+        currency_type.validate()?;
+        policy::ensure_currency_permitted(&currency_type)?;
+        let wallet = build_wallet(currency_type, algorithm, secret_key_bytes)?;
 
         // Store wallet
         let mut wallets = self.wallets.write().await;
         wallets.insert(wallet.id, wallet.clone());
+        drop(wallets);
+
+        info!(wallet = %wallet.fingerprint(), "Created {} wallet", wallet.currency_type.display_name());
+        self.request_backup().await;
+
+        Ok(wallet)
+    }
+
+    /// Get the oldest wallet of a currency, creating one if none exists
+    ///
+    /// The check and the insert happen under one lock, so concurrent callers
+    /// never create duplicates.
+    pub async fn get_or_create_wallet(&self, currency_type: CurrencyType) -> Result<Wallet> {
+        self.ensure_running()?;
+        currency_type.validate()?;
+        policy::ensure_currency_permitted(&currency_type)?;
+
+        let mut wallets = self.wallets.write().await;
+        let existing = wallets.values()
+            .filter(|w| w.currency_type == currency_type)
+            .min_by_key(|w| w.created_at);
+        if let Some(wallet) = existing {
+            return Ok(wallet.clone());
+        }
+
+        let algorithm = SigningAlgorithm::for_currency(&currency_type);
+        let wallet = build_wallet(currency_type, algorithm, self.generate_secret_key().await?)?;
+        wallets.insert(wallet.id, wallet.clone());
+        drop(wallets);
+
+        info!(wallet = %wallet.fingerprint(), "Created {} wallet", wallet.currency_type.display_name());
+        self.request_backup().await;
 
         Ok(wallet)
     }
@@ -70,12 +703,27 @@ impl WalletManager {
             .ok_or_else(|| CryptoNodeError::NotFound(format!("Wallet {} not found", id)))
     }
 
+    /// Get the minimal balance view of a wallet
+    pub async fn balance_view(&self, id: Uuid) -> Result<BalanceView> {
+        let wallets = self.wallets.read().await;
+        wallets.get(&id)
+            .map(BalanceView::from)
+            .ok_or_else(|| CryptoNodeError::NotFound(format!("Wallet {} not found", id)))
+    }
+
     /// List all wallets
     pub async fn list_wallets(&self) -> Result<Vec<Wallet>> {
         let wallets = self.wallets.read().await;
         Ok(wallets.values().cloned().collect())
     }
 
+    /// List wallets a page at a time, oldest first
+    pub async fn list_wallets_page(&self, offset: usize, limit: usize) -> Result<Page<Wallet>> {
+        let mut wallets = self.list_wallets().await?;
+        wallets.sort_by_key(|w| (w.created_at, w.id));
+        Ok(Page::from_slice(&wallets, offset, limit))
+    }
+
     /// Create a new transaction
     pub async fn create_transaction(
         &self,
@@ -83,96 +731,2867 @@ impl WalletManager {
         to_address: String,
         amount: f64,
     ) -> Result<Transaction> {
+        self.ensure_running()?;
+        self.ensure_not_safe_mode()?;
+        self.ensure_not_frozen(&from_wallet.address).await?;
+        let unsigned = self.build_unsigned_transaction(from_wallet, to_address, amount)?;
+        let mut transaction = unsigned.transaction;
+        // Watch-only wallets leave the transaction unsigned
+        if !from_wallet.private_key.is_empty() {
+            self.sign_transaction(from_wallet, &mut transaction)?;
+        }
+        self.submit_transaction(transaction).await
+    }
+
+    /// Approve, store and announce a new outgoing transaction
+    async fn submit_transaction(&self, transaction: Transaction) -> Result<Transaction> {
+        self.check_approval(&transaction).await?;
+        let reused = self.store_transaction(transaction.clone()).await?;
+        self.request_backup().await;
+
+        // Reuse harms privacy but isn't an error, so only warn
+        if reused {
+            let _ = self.event_sender.send(WalletEvent::AddressReuseWarning {
+                address: transaction.to_wallet.clone(),
+                transaction_id: transaction.id,
+            });
+        }
+
+        Ok(transaction)
+    }
+
+    /// Route `create_transaction` through a bounded queue of `depth` entries
+    ///
+    /// A single worker stores queued transactions in submission order,
+    /// batching them under one lock acquisition. Submissions beyond `depth`
+    /// fail with `ResourceBusy`. `None` stores directly again; transactions
+    /// already queued are still stored.
+    pub async fn set_transaction_queue(&self, depth: Option<usize>) -> Result<()> {
+        if depth == Some(0) {
+            return Err(CryptoNodeError::InvalidInput("Transaction queue depth cannot be zero".to_string()));
+        }
+
+        let mut queue = self.transaction_queue.write().await;
+        // Dropping the old sender lets its worker drain and exit
+        *queue = depth.map(|depth| {
+            let (tx, rx) = mpsc::channel(depth);
+            tasks::spawn("transaction-queue", run_transaction_queue(self.transactions.clone(), self.wallets.clone(), rx));
+            tx
+        });
+        Ok(())
+    }
+
+    /// Validate and record a new transaction, via the queue if one is configured
+    ///
+    /// Returns whether the recipient address was already in use. A queued
+    /// transaction is stored even if the caller stops waiting for it.
+    async fn store_transaction(&self, transaction: Transaction) -> Result<bool> {
+        let fee_model = self.fee_model(&transaction.currency_type).await;
+        let queue = self.transaction_queue.read().await.clone();
+        let Some(queue) = queue else {
+            let mut transactions = self.transactions.write().await;
+            let wallets = self.wallets.read().await;
+            return transactions.admit(&wallets, fee_model, transaction);
+        };
+
+        let (reply, stored) = oneshot::channel();
+        queue.try_send(QueuedTransaction { transaction, fee_model, reply })
+            .map_err(|e| match e {
+                mpsc::error::TrySendError::Full(_) => CryptoNodeError::ResourceBusy("Transaction queue is full".to_string()),
+                mpsc::error::TrySendError::Closed(_) => CryptoNodeError::Storage("Transaction queue has stopped".to_string()),
+            })?;
+        stored.await
+            .map_err(|_| CryptoNodeError::Storage("Transaction queue has stopped".to_string()))?
+    }
+
+    /// Move value between wallets of different currencies through an exchange
+    ///
+    /// Records two signed legs: the source wallet paying the exchange, and the
+    /// exchange paying the destination wallet the amount it returned. Every
+    /// check, including the destination cap against the exchange's quote,
+    /// runs before the exchange executes. The source leg is stored as Pending
+    /// first, reserving the funds, and is marked Failed if the exchange
+    /// fails. If the proceeds no longer fit under the cap, the destination
+    /// leg is left Pending rather than lost.
+    pub async fn transfer_between_currencies(
+        &self,
+        from_id: Uuid,
+        to_id: Uuid,
+        from_amount: f64,
+        exchange: &dyn ExchangeBackend,
+    ) -> Result<(Transaction, Transaction)> {
+        self.ensure_running()?;
+        self.ensure_not_safe_mode()?;
+        if from_amount <= 0.0 {
+            return Err(CryptoNodeError::InvalidInput("Amount must be positive".to_string()));
+        }
+
+        let from_wallet = self.get_wallet(from_id).await?;
+        let to_wallet = self.get_wallet(to_id).await?;
+        if from_wallet.currency_type == to_wallet.currency_type {
+            return Err(CryptoNodeError::InvalidInput("Wallets share a currency; use create_transaction".to_string()));
+        }
+        for currency in [&from_wallet.currency_type, &to_wallet.currency_type] {
+            currency.validate()?;
+            policy::ensure_currency_permitted(currency)?;
+        }
+        from_wallet.currency_type.check_precision(from_amount)?;
+
+        let quoted = exchange.quote(&from_wallet.currency_type, &to_wallet.currency_type, from_amount)?;
+        let quoted = check_proceeds(&to_wallet.currency_type, quoted)?;
+        check_balance(to_wallet.balance + quoted, *self.max_balance.read().await)?;
+
+        let exchange_address = normalize_address(&exchange.address());
+        let mut debit = Transaction {
+            id: Uuid::new_v4(),
+            from_wallet: from_wallet.address.clone(),
+            to_wallet: exchange_address.clone(),
+            amount: from_amount,
+            currency_type: from_wallet.currency_type.clone(),
+            timestamp: Utc::now(),
+            status: TransactionStatus::Pending,
+            fee: None,
+            signature: None,
+            category: None,
+        };
+        if !from_wallet.private_key.is_empty() {
+            self.sign_transaction(&from_wallet, &mut debit)?;
+        }
+        // Checks frozen and spendable funds under the store lock and reserves them
+        let debit = self.submit_transaction(debit).await?;
+
+        // No locks are held while the exchange runs
+        let received = exchange.execute(&from_wallet.currency_type, &to_wallet.currency_type, from_amount)
+            .and_then(|received| check_proceeds(&to_wallet.currency_type, received));
+        let received = match received {
+            Ok(received) => received,
+            Err(e) => {
+                self.set_transaction_status(debit.id, TransactionStatus::Failed, "exchange").await?;
+                return Err(e);
+            }
+        };
+        let debit = self.set_transaction_status(debit.id, TransactionStatus::Confirmed, "exchange").await?;
+
+        let mut credit = Transaction {
+            id: Uuid::new_v4(),
+            from_wallet: exchange_address,
+            to_wallet: to_wallet.address.clone(),
+            amount: received,
+            currency_type: to_wallet.currency_type.clone(),
+            timestamp: Utc::now(),
+            status: TransactionStatus::Pending,
+            fee: None,
+            signature: None,
+            category: None,
+        };
+        credit.signature = Some(exchange.sign(&credit.signing_payload()?)?);
+        self.transactions.write().await.record(credit.clone());
+        let credit = match self.set_transaction_status(credit.id, TransactionStatus::Confirmed, "exchange").await {
+            Ok(credit) => credit,
+            Err(e) => {
+                warn!(credit = %credit.id, "Exchange proceeds left pending: {}", e);
+                credit
+            }
+        };
+
+        info!(debit = %debit.id, credit = %credit.id, "Exchanged {} {} for {} {}",
+            from_amount, from_wallet.currency_type.display_name(),
+            received, to_wallet.currency_type.display_name());
+        self.request_backup().await;
+
+        Ok((debit, credit))
+    }
+
+    /// Largest amount a wallet can send in one transaction
+    ///
+    /// Accounts for debits of its pending outgoing transactions and for the
+    /// estimated fee under the currency's fee model. Returns 0 when the fee
+    /// cannot be covered.
+    pub async fn max_sendable(&self, wallet_id: Uuid) -> Result<f64> {
+        let wallet = self.get_wallet(wallet_id).await?;
+        let fee_model = self.fee_model(&wallet.currency_type).await;
+
+        let pending_debits = self.transactions.read().await.pending_debits(&wallet.address, fee_model);
+        let spendable = wallet.balance - pending_debits;
+
+        let fee = wallet.currency_type.round(ESTIMATED_FEE, self.rounding_mode());
+        Ok(wallet.currency_type.truncate(max_amount(spendable, fee, fee_model)))
+    }
+
+    /// Send a wallet's entire spendable balance to an address
+    pub async fn sweep(&self, from_id: Uuid, to_address: &str) -> Result<Transaction> {
+        let wallet = self.get_wallet(from_id).await?;
+        let amount = self.max_sendable(from_id).await?;
+        if amount <= 0.0 {
+            return Err(CryptoNodeError::InsufficientBalance(format!("Wallet {} cannot cover the fee", from_id)));
+        }
+        self.create_transaction(&wallet, to_address.to_string(), amount).await
+    }
+
+    /// Build a transaction without signing it
+    ///
+    /// The returned payload can be signed by an external or air-gapped
+    /// device and handed back to `attach_signature`.
+    pub fn build_unsigned_transaction(
+        &self,
+        from_wallet: &Wallet,
+        to_address: String,
+        amount: f64,
+    ) -> Result<UnsignedTransaction> {
+        if from_wallet.frozen {
+            return Err(CryptoNodeError::PermissionDenied(format!("Wallet {} is frozen", from_wallet.id)));
+        }
+
+        from_wallet.currency_type.validate()?;
+        policy::ensure_currency_permitted(&from_wallet.currency_type)?;
+
         // Validate amount
         if amount <= 0.0 {
             return Err(CryptoNodeError::InvalidInput("Amount must be positive".to_string()));
         }
+        from_wallet.currency_type.check_precision(amount)?;
 
-        // Check balance
+        // Check balance; pending debits and the fee are checked when it is stored
         if from_wallet.balance < amount {
-            return Err(CryptoNodeError::InvalidInput("Insufficient balance".to_string()));
+            return Err(CryptoNodeError::InsufficientBalance(format!("Wallet {} cannot cover {}", from_wallet.id, amount)));
         }
 
-        // Create transaction
         let transaction = Transaction {
             id: Uuid::new_v4(),
             from_wallet: from_wallet.address.clone(),
-            to_wallet: to_address,
+            to_wallet: normalize_address(&to_address),
             amount,
-            currency_type: from_wallet.currency_type,
+            currency_type: from_wallet.currency_type.clone(),
             timestamp: Utc::now(),
             status: TransactionStatus::Pending,
-            fee: Some(0.001), // Example fee, should be calculated based on network conditions
+            fee: Some(from_wallet.currency_type.round(ESTIMATED_FEE, self.rounding_mode())),
+            signature: None,
+            category: None,
         };
+        let payload = transaction.signing_payload()?;
 
-        // Store transaction
-        let mut transactions = self.transactions.write().await;
-        transactions.push(transaction.clone());
-
-        Ok(transaction)
+        Ok(UnsignedTransaction { transaction, payload })
     }
 
-    /// Update transaction status
-    pub async fn update_transaction_status(
+    /// Attach an externally produced signature to an unsigned transaction
+    ///
+    /// The signature is verified against the payload and the public key must
+    /// belong to the sending address. The transaction then goes through the
+    /// same checks as `create_transaction` and is recorded as Pending,
+    /// whatever status it carried. Attaching the same transaction twice fails.
+    pub async fn attach_signature(
         &self,
-        transaction_id: Uuid,
-        status: TransactionStatus,
+        unsigned: UnsignedTransaction,
+        signature: &[u8],
+        public_key: &[u8],
     ) -> Result<Transaction> {
-        let mut transactions = self.transactions.write().await;
-        
-        let transaction = transactions.iter_mut()
-            .find(|t| t.id == transaction_id)
-            .ok_or_else(|| CryptoNodeError::NotFound(format!("Transaction {} not found", transaction_id)))?;
+        self.ensure_running()?;
+        let mut transaction = unsigned.transaction;
+        self.ensure_not_safe_mode()?;
+        self.ensure_not_frozen(&transaction.from_wallet).await?;
+
+        if !same_address(&hex::encode(public_key), &transaction.from_wallet) {
+            return Err(CryptoNodeError::Security("Public key does not match sending address".to_string()));
+        }
 
-        // Update transaction status
-        transaction.status = status;
+        // Recompute rather than trusting the carried payload
+        let payload = transaction.signing_payload()?;
+        if payload != unsigned.payload {
+            return Err(CryptoNodeError::Security("Signing payload does not match transaction".to_string()));
+        }
 
-        // If confirmed, update wallet balances
-        if status == TransactionStatus::Confirmed {
-            let mut wallets = self.wallets.write().await;
-            
-            // Find and update sender's wallet
-            for wallet in wallets.values_mut() {
-                if wallet.address == transaction.from_wallet {
-                    wallet.balance -= transaction.amount + transaction.fee.unwrap_or(0.0);
-                    wallet.last_updated = Utc::now();
-                }
-            }
+        if !crypto::verify_any(public_key, &payload, signature)? {
+            return Err(CryptoNodeError::Security("Signature verification failed".to_string()));
         }
 
-        Ok(transaction.clone())
+        // Status isn't signed, so never trust the one supplied
+        transaction.status = TransactionStatus::Pending;
+        transaction.signature = Some(signature.to_vec());
+        self.submit_transaction(transaction).await
     }
 
-    /// Get transaction history for a wallet
-    pub async fn get_transaction_history(&self, wallet_address: &str) -> Result<Vec<Transaction>> {
+    /// Sign a transaction with the sending wallet's key
+    ///
+    /// Signs `Transaction::signing_payload`, whose byte layout doesn't depend
+    /// on serialization order, and stores the result in `signature`.
+    pub fn sign_transaction(&self, wallet: &Wallet, tx: &mut Transaction) -> Result<()> {
+        if !same_address(&wallet.address, &tx.from_wallet) {
+            return Err(CryptoNodeError::InvalidInput("Transaction was not sent from this wallet".to_string()));
+        }
+        if wallet.private_key.is_empty() {
+            return Err(CryptoNodeError::PermissionDenied(format!("Wallet {} has no private key", wallet.id)));
+        }
+        let payload = tx.signing_payload()?;
+        tx.signature = Some(wallet.signing_algorithm.sign(&wallet.private_key, &payload)?);
+        Ok(())
+    }
+
+    /// Check a transaction's signature against the sender's public key
+    ///
+    /// Unsigned transactions and keys that don't belong to the sending
+    /// address verify as `false`.
+    pub fn verify_transaction(&self, tx: &Transaction, public_key: &[u8]) -> Result<bool> {
+        let Some(signature) = &tx.signature else {
+            return Ok(false);
+        };
+        if !same_address(&hex::encode(public_key), &tx.from_wallet) {
+            return Ok(false);
+        }
+        crypto::verify_any(public_key, &tx.signing_payload()?, signature)
+    }
+
+    /// Sign an arbitrary message to prove ownership of a wallet's address
+    ///
+    /// The message is domain-separated, so the signature cannot be passed
+    /// off as a transaction signature.
+    pub async fn sign_message(&self, id: Uuid, message: &[u8]) -> Result<Vec<u8>> {
+        let wallet = self.get_wallet(id).await?;
+        if wallet.frozen {
+            return Err(CryptoNodeError::PermissionDenied(format!("Wallet {} is frozen", wallet.id)));
+        }
+        if wallet.private_key.is_empty() {
+            return Err(CryptoNodeError::PermissionDenied(format!("Wallet {} has no private key", wallet.id)));
+        }
+        wallet.signing_algorithm.sign(&wallet.private_key, &crypto::message_payload(message))
+    }
+
+    /// Update transaction status
+    ///
+    /// Confirming applies the sender debit and recipient credit. It fails,
+    /// leaving the status unchanged, if the sender can no longer cover the
+    /// debit or a credit would exceed the balance cap.
+    pub async fn update_transaction_status(
+        &self,
+        transaction_id: Uuid,
+        status: TransactionStatus,
+    ) -> Result<Transaction> {
+        self.set_transaction_status(transaction_id, status, "status_update").await
+    }
+
+    /// Change a transaction's status, applying balances when it confirms
+    async fn set_transaction_status(
+        &self,
+        transaction_id: Uuid,
+        status: TransactionStatus,
+        trigger: &str,
+    ) -> Result<Transaction> {
+        self.ensure_running()?;
+        let mut transactions = self.transactions.write().await;
+        
+        let transaction = transactions.get_mut(transaction_id)
+            .ok_or_else(|| CryptoNodeError::NotFound(format!("Transaction {} not found", transaction_id)))?;
+
+        let previous = transaction.status;
+
+        // If newly confirmed, update wallet balances
+        if status == TransactionStatus::Confirmed && previous != TransactionStatus::Confirmed {
+            let fee_model = self.fee_model(&transaction.currency_type).await;
+            let (debit, credit) = fee_model.settle(transaction.amount, transaction.fee.unwrap_or(0.0));
+            let max_balance = *self.max_balance.read().await;
+            let mut wallets = self.wallets.write().await;
+
+            // Reject before anything changes if the sender can't cover the
+            // debit or a credit would break the cap
+            for wallet in wallets.values() {
+                let is_sender = same_address(&wallet.address, &transaction.from_wallet);
+                let is_recipient = receives_at(wallet, &transaction.to_wallet);
+                let mut balance = wallet.balance;
+                if is_sender {
+                    balance -= debit;
+                }
+                if is_recipient {
+                    balance += credit;
+                }
+                if is_sender && balance < 0.0 {
+                    return Err(CryptoNodeError::InsufficientBalance(format!(
+                        "Insufficient balance in wallet {} to confirm transaction {}", wallet.id, transaction_id
+                    )));
+                }
+                if is_recipient {
+                    check_balance(balance, max_balance)?;
+                }
+            }
+
+            transaction.status = status;
+            log_transition(transaction, previous, trigger);
+
+            // Find and update sender's and any local recipient's wallet
+            for wallet in wallets.values_mut() {
+                let is_sender = same_address(&wallet.address, &transaction.from_wallet);
+                let is_recipient = receives_at(wallet, &transaction.to_wallet);
+                if !is_sender && !is_recipient {
+                    continue;
+                }
+
+                let old_balance = wallet.balance;
+                if is_sender {
+                    wallet.balance -= debit;
+                }
+                if is_recipient {
+                    wallet.balance += credit;
+                }
+                wallet.last_updated = Utc::now();
+
+                let _ = self.event_sender.send(WalletEvent::BalanceChanged {
+                    wallet_id: wallet.id,
+                    old_balance,
+                    new_balance: wallet.balance,
+                    reason: BalanceChangeReason::Transaction,
+                });
+            }
+        } else {
+            transaction.status = status;
+            log_transition(transaction, previous, trigger);
+        }
+
+        let updated = transaction.clone();
+        drop(transactions);
+        self.request_backup().await;
+        Ok(updated)
+    }
+
+    /// Set how fees are applied for a currency
+    pub async fn set_fee_model(&self, currency: CurrencyType, model: FeeModel) {
+        self.fee_models.write().await.insert(currency, model);
+    }
+
+    /// Get the fee model for a currency, defaulting to `AddedToAmount`
+    pub async fn fee_model(&self, currency: &CurrencyType) -> FeeModel {
+        self.fee_models.read().await.get(currency).copied().unwrap_or_default()
+    }
+
+    /// Set the estimator used by `estimate_confirmation_time`
+    pub async fn set_confirmation_estimator(&self, estimator: Option<Arc<dyn ConfirmationEstimator>>) {
+        *self.confirmation_estimator.write().await = estimator;
+    }
+
+    /// Estimate how long until a pending transaction confirms
+    ///
+    /// Returns `None` when no estimator is configured, the estimator has no
+    /// estimate, or the transaction is no longer pending.
+    pub async fn estimate_confirmation_time(&self, transaction_id: Uuid) -> Result<Option<Duration>> {
+        let transaction = self.find_transaction(transaction_id).await?;
+
+        if transaction.status != TransactionStatus::Pending {
+            return Ok(None);
+        }
+
+        let estimator = self.confirmation_estimator.read().await.clone();
+        match estimator {
+            Some(estimator) => estimator.estimate(&transaction),
+            None => Ok(None),
+        }
+    }
+
+    /// Get a single transaction by its ID
+    pub async fn find_transaction(&self, transaction_id: Uuid) -> Result<Transaction> {
         let transactions = self.transactions.read().await;
-        Ok(transactions.iter()
-            .filter(|t| t.from_wallet == wallet_address || t.to_wallet == wallet_address)
+        transactions.get(transaction_id)
             .cloned()
+            .ok_or_else(|| CryptoNodeError::NotFound(format!("Transaction {} not found", transaction_id)))
+    }
+
+    /// Get transaction history for a wallet
+    pub async fn get_transaction_history(&self, wallet_address: &str) -> Result<Vec<Transaction>> {
+        let transactions = self.transactions.read().await;
+        Ok(transactions.history(wallet_address))
+    }
+
+    /// Get transaction history for a wallet a page at a time
+    pub async fn get_transaction_history_page(
+        &self,
+        wallet_address: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Page<Transaction>> {
+        let history = self.get_transaction_history(wallet_address).await?;
+        Ok(Page::from_slice(&history, offset, limit))
+    }
+
+    /// Get transaction history for a wallet in a single currency
+    pub async fn get_transaction_history_by_currency(
+        &self,
+        wallet_address: &str,
+        currency: &CurrencyType,
+    ) -> Result<Vec<Transaction>> {
+        let transactions = self.transactions.read().await;
+        Ok(transactions.history(wallet_address)
+            .into_iter()
+            .filter(|t| &t.currency_type == currency)
+            .collect())
+    }
+
+    /// Label a transaction for reporting, or clear the label with `None`
+    ///
+    /// Categories are local annotations and do not affect the signature.
+    pub async fn set_transaction_category(
+        &self,
+        transaction_id: Uuid,
+        category: Option<String>,
+    ) -> Result<Transaction> {
+        self.ensure_running()?;
+        let mut transactions = self.transactions.write().await;
+        let transaction = transactions.get_mut(transaction_id)
+            .ok_or_else(|| CryptoNodeError::NotFound(format!("Transaction {} not found", transaction_id)))?;
+        transaction.category = category;
+        let updated = transaction.clone();
+        drop(transactions);
+        self.request_backup().await;
+        Ok(updated)
+    }
+
+    /// Get transaction history for a wallet with the given category
+    pub async fn transactions_by_category(
+        &self,
+        wallet_address: &str,
+        category: &str,
+    ) -> Result<Vec<Transaction>> {
+        let transactions = self.transactions.read().await;
+        Ok(transactions.history(wallet_address)
+            .into_iter()
+            .filter(|t| t.category.as_deref() == Some(category))
             .collect())
     }
 
+    /// Get the transactions between two addresses, in either direction, oldest first
+    pub async fn transactions_with(&self, my_address: &str, counterparty: &str) -> Result<Vec<Transaction>> {
+        let transactions = self.transactions.read().await;
+        let mut between: Vec<Transaction> = transactions.history(my_address)
+            .into_iter()
+            .filter(|t| {
+                (same_address(&t.from_wallet, my_address) && same_address(&t.to_wallet, counterparty))
+                    || (same_address(&t.from_wallet, counterparty) && same_address(&t.to_wallet, my_address))
+            })
+            .collect();
+        between.sort_by_key(|t| t.timestamp);
+        Ok(between)
+    }
+
+    /// Sum the fees of an address's confirmed outgoing transactions
+    ///
+    /// Only transactions at or after `since` are counted when it is given.
+    pub async fn total_fees_paid(&self, address: &str, since: Option<DateTime<Utc>>) -> Result<f64> {
+        let transactions = self.transactions.read().await;
+        Ok(transactions.history(address)
+            .iter()
+            .filter(|t| t.status == TransactionStatus::Confirmed)
+            .filter(|t| same_address(&t.from_wallet, address))
+            .filter(|t| since.is_none_or(|since| t.timestamp >= since))
+            .filter_map(|t| t.fee)
+            .sum())
+    }
+
+    /// Reconstruct an address's balance at a past point in time
+    ///
+    /// Replays confirmed transactions timestamped at or before `at`, applying
+    /// each currency's fee model. Balance changes from rewards or manual
+    /// updates are not transactions and are not included.
+    pub async fn balance_at(&self, address: &str, at: DateTime<Utc>) -> Result<f64> {
+        let fee_models = self.fee_models.read().await.clone();
+        let transactions = self.transactions.read().await;
+
+        let mut balance = 0.0;
+        for transaction in transactions.history(address) {
+            if transaction.status != TransactionStatus::Confirmed || transaction.timestamp > at {
+                continue;
+            }
+            let fee_model = fee_models.get(&transaction.currency_type).copied().unwrap_or_default();
+            let (debit, credit) = fee_model.settle(transaction.amount, transaction.fee.unwrap_or(0.0));
+            if same_address(&transaction.from_wallet, address) {
+                balance -= debit;
+            }
+            if same_address(&transaction.to_wallet, address) {
+                balance += credit;
+            }
+        }
+
+        Ok(balance)
+    }
+
+    /// Summarize a wallet's age, activity and transaction counts
+    pub async fn wallet_summary(&self, id: Uuid) -> Result<WalletSummary> {
+        let wallet = self.get_wallet(id).await?;
+        let history = self.get_transaction_history(&wallet.address).await?;
+
+        let mut summary = WalletSummary {
+            wallet_id: wallet.id,
+            age: Utc::now() - wallet.created_at,
+            last_activity: history.iter().map(|t| t.timestamp).max(),
+            pending: 0,
+            confirmed: 0,
+            failed: 0,
+            balance: wallet.balance,
+        };
+        for transaction in &history {
+            match transaction.status {
+                TransactionStatus::Pending => summary.pending += 1,
+                TransactionStatus::Confirmed => summary.confirmed += 1,
+                TransactionStatus::Failed => summary.failed += 1,
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Wallets with funds on the way, and how much each will receive
+    ///
+    /// Totals come from pending transactions sent to the wallet, after fees
+    /// under the currency's fee model. Wallets with nothing pending are omitted.
+    pub async fn wallets_with_pending_incoming(&self) -> Vec<(Uuid, f64)> {
+        let fee_models = self.fee_models.read().await.clone();
+        let transactions = self.transactions.read().await;
+        let wallets = self.wallets.read().await;
+
+        let mut incoming = Vec::new();
+        for wallet in wallets.values() {
+            let total: f64 = transactions.wallet_history(wallet)
+                .iter()
+                .filter(|t| t.status == TransactionStatus::Pending)
+                .filter(|t| receives_at(wallet, &t.to_wallet) && !same_address(&t.from_wallet, &wallet.address))
+                .map(|t| {
+                    let fee_model = fee_models.get(&t.currency_type).copied().unwrap_or_default();
+                    fee_model.settle(t.amount, t.fee.unwrap_or(0.0)).1
+                })
+                .sum();
+            if total > 0.0 {
+                incoming.push((wallet.id, total));
+            }
+        }
+
+        incoming
+    }
+
+    /// Set the limits used to flag wallets needing attention
+    pub async fn set_attention_thresholds(&self, thresholds: AttentionThresholds) {
+        *self.attention_thresholds.write().await = thresholds;
+    }
+
+    /// List wallets that need user action, one item per reason
+    pub async fn wallets_needing_attention(&self) -> Vec<AttentionItem> {
+        let thresholds = *self.attention_thresholds.read().await;
+        let now = Utc::now();
+        let transactions = self.transactions.read().await;
+        let wallets = self.wallets.read().await;
+        let mut items = Vec::new();
+
+        for wallet in wallets.values() {
+            let mut flag = |reason| items.push(AttentionItem { wallet_id: wallet.id, reason });
+            let history = transactions.history(&wallet.address);
+
+            if history.iter().any(|t| {
+                t.status == TransactionStatus::Pending && now - t.timestamp > thresholds.pending_age
+            }) {
+                flag(AttentionReason::StalePendingTransaction);
+            }
+            if wallet.balance < thresholds.low_balance {
+                flag(AttentionReason::LowBalance);
+            }
+            if now - wallet.last_updated > thresholds.stale_after {
+                flag(AttentionReason::StaleWallet);
+            }
+            if history.iter().any(|t| {
+                t.status == TransactionStatus::Failed && now - t.timestamp <= thresholds.failure_window
+            }) {
+                flag(AttentionReason::RecentFailure);
+            }
+        }
+
+        items
+    }
+
+    /// Merge another node's transaction history into this one
+    ///
+    /// Transactions are matched by id. Unknown ones are added; status
+    /// disagreements resolve as Confirmed > Failed > Pending and are listed
+    /// in the report. Every status change, including adding a transaction
+    /// that isn't Pending, goes through `update_transaction_status`, so
+    /// balances follow as if it had happened locally. Changes it refuses are
+    /// listed in `rejected`.
+    ///
+    /// Fails without merging anything if a remote signature doesn't verify,
+    /// or if a remote transaction spending from a local wallet is unsigned.
+    pub async fn merge_history(&self, other: Vec<Transaction>) -> Result<MergeReport> {
+        self.ensure_running()?;
+        let wallets = self.wallets.read().await;
+        for remote in &other {
+            remote.check_amounts()?;
+            match &remote.signature {
+                Some(_) if !signature_valid(remote) => {
+                    return Err(CryptoNodeError::Security(format!("Signature of transaction {} does not verify", remote.id)));
+                }
+                // Confirming it would debit our wallet with no proof we authorized it
+                None if wallets.values().any(|w| same_address(&w.address, &remote.from_wallet)) => {
+                    return Err(CryptoNodeError::Security(format!("Transaction {} from a local wallet is unsigned", remote.id)));
+                }
+                _ => {}
+            }
+        }
+        drop(wallets); // Lock order is transactions, then wallets
+        let mut transactions = self.transactions.write().await;
+        let mut report = MergeReport::default();
+        let mut changes = Vec::new();
+        let mut adopted_signature = false;
+
+        for remote in other {
+            let Some(local) = transactions.get_mut(remote.id) else {
+                // Added as Pending so confirming it applies its balances
+                if remote.status != TransactionStatus::Pending {
+                    changes.push((remote.id, remote.status));
+                }
+                transactions.push(Transaction { status: TransactionStatus::Pending, ..remote });
+                report.added += 1;
+                continue;
+            };
+
+            if local.status == remote.status {
+                report.unchanged += 1;
+                continue;
+            }
+
+            let resolved = if status_precedence(remote.status) > status_precedence(local.status) {
+                remote.status
+            } else {
+                local.status
+            };
+            report.conflicts.push(MergeConflict {
+                transaction_id: remote.id,
+                local: local.status,
+                remote: remote.status,
+                resolved,
+            });
+            if resolved != local.status {
+                changes.push((remote.id, resolved));
+            }
+            if local.signature.is_none() && remote.signature.is_some() {
+                local.signature = remote.signature;
+                adopted_signature = true;
+            }
+        }
+        drop(transactions);
+        // Status changes request their own backups
+        if report.added > 0 || adopted_signature {
+            self.request_backup().await;
+        }
+
+        for (transaction_id, status) in changes {
+            if let Err(e) = self.set_transaction_status(transaction_id, status, "merge").await {
+                warn!(%transaction_id, "Merged status {:?} was not applied: {}", status, e);
+                report.rejected.push(transaction_id);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Re-verify every stored transaction signature
+    ///
+    /// Returns the ids of transactions whose signature fails verification.
+    /// Unsigned transactions are skipped.
+    pub async fn verify_all_signatures(&self) -> Result<Vec<Uuid>> {
+        let transactions = self.transactions.read().await;
+        let mut failed = Vec::new();
+
+        for transaction in &transactions.transactions {
+            if transaction.signature.is_none() {
+                continue;
+            }
+
+            if !signature_valid(transaction) {
+                failed.push(transaction.id);
+            }
+        }
+
+        Ok(failed)
+    }
+
     /// Update wallet balance
     pub async fn update_wallet_balance(&self, wallet_id: Uuid, new_balance: f64) -> Result<Wallet> {
+        self.update_wallet_balance_with_reason(wallet_id, new_balance, BalanceChangeReason::Manual).await
+    }
+
+    /// Update wallet balance, recording why it changed in the emitted event
+    pub async fn update_wallet_balance_with_reason(
+        &self,
+        wallet_id: Uuid,
+        new_balance: f64,
+        reason: BalanceChangeReason,
+    ) -> Result<Wallet> {
+        self.ensure_running()?;
+        check_balance(new_balance, *self.max_balance.read().await)?;
         let mut wallets = self.wallets.write().await;
         
         let wallet = wallets.get_mut(&wallet_id)
             .ok_or_else(|| CryptoNodeError::NotFound(format!("Wallet {} not found", wallet_id)))?;
 
+        let old_balance = wallet.balance;
         wallet.balance = new_balance;
         wallet.last_updated = Utc::now();
 
-        Ok(wallet.clone())
+        let _ = self.event_sender.send(WalletEvent::BalanceChanged {
+            wallet_id,
+            old_balance,
+            new_balance,
+            reason,
+        });
+
+        let updated = wallet.clone();
+        drop(wallets);
+        self.request_backup().await;
+        Ok(updated)
+    }
+
+    /// Add to a wallet's balance in one step
+    ///
+    /// Unlike reading the balance and calling `update_wallet_balance`, a
+    /// concurrent change can't be overwritten.
+    pub async fn credit_wallet(&self, wallet_id: Uuid, amount: f64, reason: BalanceChangeReason) -> Result<Wallet> {
+        self.ensure_running()?;
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(CryptoNodeError::InvalidInput(format!("Invalid credit amount {}", amount)));
+        }
+        let max_balance = *self.max_balance.read().await;
+        let mut wallets = self.wallets.write().await;
+        let wallet = wallets.get_mut(&wallet_id)
+            .ok_or_else(|| CryptoNodeError::NotFound(format!("Wallet {} not found", wallet_id)))?;
+
+        let old_balance = wallet.balance;
+        check_balance(old_balance + amount, max_balance)?;
+        wallet.balance += amount;
+        wallet.last_updated = Utc::now();
+
+        let _ = self.event_sender.send(WalletEvent::BalanceChanged {
+            wallet_id,
+            old_balance,
+            new_balance: wallet.balance,
+            reason,
+        });
+
+        let updated = wallet.clone();
+        drop(wallets);
+        self.request_backup().await;
+        Ok(updated)
+    }
+
+    /// Derive a fresh receive address for a wallet, e.g. one per invoice
+    ///
+    /// Addresses are derived deterministically from the wallet's public key
+    /// and an index stored on the wallet. Confirmed payments to any of them
+    /// credit the wallet.
+    pub async fn next_receive_address(&self, wallet_id: Uuid) -> Result<String> {
+        self.ensure_running()?;
+        let mut wallets = self.wallets.write().await;
+        let wallet = wallets.get_mut(&wallet_id)
+            .ok_or_else(|| CryptoNodeError::NotFound(format!("Wallet {} not found", wallet_id)))?;
+
+        let index = u32::try_from(wallet.receive_addresses.len())
+            .map_err(|_| CryptoNodeError::InvalidInput("Receive address index exhausted".to_string()))?;
+        let address = crypto::derive_receive_address(&wallet.public_key, index);
+        wallet.receive_addresses.push(address.clone());
+        wallet.last_updated = Utc::now();
+        drop(wallets);
+
+        self.request_backup().await;
+        Ok(address)
+    }
+
+    /// Set how estimated fees and reward payouts are rounded
+    pub fn set_rounding_mode(&self, mode: RoundingMode) {
+        *self.rounding_mode.lock().unwrap_or_else(|e| e.into_inner()) = mode;
+    }
+
+    /// Rounding mode for estimated fees and reward payouts
+    pub fn rounding_mode(&self) -> RoundingMode {
+        *self.rounding_mode.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Cap the balance any wallet may reach, or remove the cap with `None`
+    ///
+    /// Credits that would exceed the cap, or produce a non-finite balance,
+    /// are rejected with `InvalidInput`. Defaults to `DEFAULT_MAX_BALANCE`.
+    pub async fn set_max_balance(&self, max: Option<f64>) -> Result<()> {
+        if max.is_some_and(|max| !max.is_finite() || max <= 0.0) {
+            return Err(CryptoNodeError::InvalidInput("Maximum balance must be positive and finite".to_string()));
+        }
+        *self.max_balance.write().await = max;
+        Ok(())
+    }
+
+    /// Freeze a wallet so no transactions can be sent from it
+    ///
+    /// Incoming credits and queries are unaffected.
+    pub async fn freeze_wallet(&self, wallet_id: Uuid) -> Result<Wallet> {
+        self.set_frozen(wallet_id, true).await
+    }
+
+    /// Unfreeze a wallet, allowing it to send transactions again
+    pub async fn unfreeze_wallet(&self, wallet_id: Uuid) -> Result<Wallet> {
+        self.set_frozen(wallet_id, false).await
+    }
+
+    async fn set_frozen(&self, wallet_id: Uuid, frozen: bool) -> Result<Wallet> {
+        self.ensure_running()?;
+        let mut wallets = self.wallets.write().await;
+
+        let wallet = wallets.get_mut(&wallet_id)
+            .ok_or_else(|| CryptoNodeError::NotFound(format!("Wallet {} not found", wallet_id)))?;
+
+        wallet.frozen = frozen;
+        wallet.last_updated = Utc::now();
+
+        let updated = wallet.clone();
+        drop(wallets);
+        self.request_backup().await;
+        Ok(updated)
+    }
+
+    /// Check the stored wallet for an address isn't frozen
+    async fn ensure_not_frozen(&self, address: &str) -> Result<()> {
+        let wallets = self.wallets.read().await;
+        match wallets.values().find(|w| same_address(&w.address, address)) {
+            Some(wallet) if wallet.frozen => {
+                Err(CryptoNodeError::PermissionDenied(format!("Wallet {} is frozen", wallet.id)))
+            }
+            _ => Ok(()),
+        }
     }
 
     /// Delete a wallet
     pub async fn delete_wallet(&self, wallet_id: Uuid) -> Result<()> {
+        self.ensure_running()?;
         let mut wallets = self.wallets.write().await;
         
         if wallets.remove(&wallet_id).is_none() {
             return Err(CryptoNodeError::NotFound(format!("Wallet {} not found", wallet_id)));
         }
+        drop(wallets);
+
+        let _ = self.event_sender.send(WalletEvent::WalletDeleted { wallet_id });
+        self.request_backup().await;
 
         Ok(())
     }
-} 
\ No newline at end of file
+
+    /// Find transactions that no managed wallet is a party to
+    ///
+    /// These are left behind when a wallet is deleted, since its history is
+    /// kept for auditing.
+    pub async fn find_orphaned_transactions(&self) -> Vec<Uuid> {
+        let transactions = self.transactions.read().await;
+        let wallets = self.wallets.read().await;
+        transactions.transactions.iter()
+            .filter(|t| is_orphaned(t, &wallets))
+            .map(|t| t.id)
+            .collect()
+    }
+
+    /// Remove orphaned transactions, returning the ids removed
+    pub async fn remove_orphaned_transactions(&self) -> Result<Vec<Uuid>> {
+        self.ensure_running()?;
+        let mut transactions = self.transactions.write().await;
+        let wallets = self.wallets.read().await;
+        let mut removed = Vec::new();
+        transactions.transactions.retain(|t| {
+            let orphaned = is_orphaned(t, &wallets);
+            if orphaned {
+                removed.push(t.id);
+            }
+            !orphaned
+        });
+        drop(wallets);
+
+        if removed.is_empty() {
+            return Ok(removed);
+        }
+        transactions.rebuild_index();
+        drop(transactions);
+
+        info!("Removed {} orphaned transactions", removed.len());
+        self.request_backup().await;
+        Ok(removed)
+    }
+
+    /// Configure automatic encrypted backups after wallet changes
+    ///
+    /// Changes are batched: a backup is written once no further change
+    /// arrives for `debounce_secs`, once `max_batch` changes are pending, or
+    /// once the oldest pending change is `max_delay_secs` old, whichever
+    /// comes first. Backup failures are logged and never fail the
+    /// triggering operation.
+    pub async fn configure_auto_backup(&self, config: AutoBackupConfig) {
+        let mut trigger = self.backup_trigger.write().await;
+        if !config.enabled {
+            *trigger = None;
+            return;
+        }
+
+        // Changes are counted separately, so dropped Changed signals lose nothing
+        let (tx, mut rx) = mpsc::channel::<BackupSignal>(16);
+        let wallets = self.wallets.clone();
+        let transactions = self.transactions.clone();
+        let pending_changes = self.pending_changes.clone();
+        let debounce = Duration::from_secs(config.debounce_secs);
+        let max_delay = Duration::from_secs(config.max_delay_secs);
+        let max_batch = config.max_batch.max(1);
+        let path = config.path.clone();
+
+        tasks::spawn("wallet-auto-backup", async move {
+            let mut closed = false;
+            while !closed {
+                let mut reply = match rx.recv().await {
+                    Some(BackupSignal::Changed) => None,
+                    Some(BackupSignal::FlushNow(reply)) => Some(reply),
+                    None => break,
+                };
+
+                // Coalesce further changes until a flush condition is met
+                let deadline = Instant::now() + max_delay;
+                while reply.is_none() && pending_changes.load(Ordering::SeqCst) < max_batch {
+                    let wait = debounce.min(deadline.saturating_duration_since(Instant::now()));
+                    match timeout(wait, rx.recv()).await {
+                        Ok(Some(BackupSignal::Changed)) => continue,
+                        Ok(Some(BackupSignal::FlushNow(flush))) => reply = Some(flush),
+                        Ok(None) => {
+                            closed = true;
+                            break;
+                        }
+                        Err(_) => break,
+                    }
+                }
+
+                pending_changes.store(0, Ordering::SeqCst);
+                let result = write_backup(&path, &config.passphrase_source, &wallets, &transactions).await;
+                match &result {
+                    Ok(()) => info!("Wallet backup written to {}", path.display()),
+                    Err(e) => warn!("Automatic wallet backup failed: {}", e),
+                }
+                if let Some(reply) = reply {
+                    let _ = reply.send(result);
+                }
+            }
+        });
+
+        *trigger = Some(BackupTrigger { path: config.path, signals: tx });
+    }
+
+    /// Signal the auto-backup task, if configured
+    async fn request_backup(&self) {
+        if let Some(trigger) = self.backup_trigger.read().await.as_ref() {
+            self.pending_changes.fetch_add(1, Ordering::SeqCst);
+            let _ = trigger.signals.try_send(BackupSignal::Changed);
+        }
+    }
+
+    /// Write a backup immediately instead of waiting for the batch to fill
+    pub async fn flush_now(&self) -> Result<()> {
+        let trigger = self.backup_trigger.read().await.clone()
+            .ok_or_else(|| CryptoNodeError::Storage("Automatic backups are not configured".to_string()))?;
+        flush_via(&trigger.signals).await
+    }
+
+    /// Flush pending changes and stop the auto-backup task
+    ///
+    /// Afterwards, methods that change wallets or transactions fail with
+    /// `CryptoNodeError::Cancelled` so late writes can't be silently lost.
+    /// Queries keep working.
+    pub async fn shutdown(&self) -> Result<()> {
+        self.shut_down.store(true, Ordering::SeqCst);
+        let Some(trigger) = self.backup_trigger.write().await.take() else {
+            return Ok(());
+        };
+        flush_via(&trigger.signals).await
+    }
+
+    /// Get transaction creation statistics
+    pub async fn transaction_metrics(&self) -> TransactionMetrics {
+        self.transactions.read().await.metrics()
+    }
+
+    /// Cap the number of stored transactions
+    ///
+    /// When exceeded, the oldest failed transactions are pruned. `None`
+    /// removes the cap.
+    pub async fn set_max_transactions(&self, max: Option<usize>) {
+        let mut transactions = self.transactions.write().await;
+        transactions.max_transactions = max;
+        transactions.enforce_cap();
+    }
+
+    /// Capture the entire in-memory state, including private keys
+    pub async fn snapshot(&self) -> WalletSnapshot {
+        // Lock order matches update_transaction_status: transactions, then wallets
+        let transactions = self.transactions.read().await;
+        let wallets = self.wallets.read().await;
+        WalletSnapshot {
+            wallets: wallets.clone(),
+            transactions: transactions.transactions.clone(),
+            redacted: false,
+        }
+    }
+
+    /// Capture the in-memory state with private keys removed
+    ///
+    /// Suitable for inspection and logging; it cannot be restored.
+    pub async fn snapshot_redacted(&self) -> WalletSnapshot {
+        let mut snapshot = self.snapshot().await;
+        for wallet in snapshot.wallets.values_mut() {
+            wallet.private_key.clear();
+        }
+        snapshot.redacted = true;
+        snapshot
+    }
+
+    /// Replace the entire in-memory state with a snapshot
+    pub async fn restore(&self, snapshot: WalletSnapshot) -> Result<()> {
+        self.ensure_running()?;
+        if snapshot.redacted {
+            return Err(CryptoNodeError::InvalidInput("Cannot restore a redacted snapshot".to_string()));
+        }
+        for wallet in snapshot.wallets.values() {
+            wallet.check_amounts()?;
+            // Watch-only wallets have no key to validate
+            if !wallet.private_key.is_empty() {
+                wallet.signing_algorithm.validate_secret_key(&wallet.private_key)?;
+            }
+        }
+        for transaction in &snapshot.transactions {
+            transaction.check_amounts()?;
+        }
+
+        let mut transactions = self.transactions.write().await;
+        let mut wallets = self.wallets.write().await;
+        transactions.replace(snapshot.transactions);
+        *wallets = snapshot.wallets;
+        drop(wallets);
+        drop(transactions);
+
+        self.request_backup().await;
+        Ok(())
+    }
+} 
+
+/// Check a new outgoing transaction against its stored sending wallet
+///
+/// Returns the sender. Whether it can afford the transaction is left to
+/// `check_spendable`, which needs its pending debits.
+fn check_outgoing<'a>(wallets: &'a HashMap<Uuid, Wallet>, transaction: &Transaction) -> Result<&'a Wallet> {
+    transaction.check_amounts()?;
+    let sender = wallets.values()
+        .find(|w| same_address(&w.address, &transaction.from_wallet))
+        .ok_or_else(|| CryptoNodeError::NotFound(format!("No wallet sends from {}", transaction.from_wallet)))?;
+    if sender.frozen {
+        return Err(CryptoNodeError::PermissionDenied(format!("Wallet {} is frozen", sender.id)));
+    }
+    if sender.currency_type != transaction.currency_type {
+        return Err(CryptoNodeError::InvalidInput("Transaction currency does not match the sending wallet".to_string()));
+    }
+    transaction.currency_type.validate()?;
+    policy::ensure_currency_permitted(&transaction.currency_type)?;
+    if transaction.amount <= 0.0 {
+        return Err(CryptoNodeError::InvalidInput("Amount must be positive".to_string()));
+    }
+    transaction.currency_type.check_precision(transaction.amount)?;
+    Ok(sender)
+}
+
+/// Truncate exchange proceeds to the currency's precision, rejecting unusable amounts
+fn check_proceeds(currency: &CurrencyType, amount: f64) -> Result<f64> {
+    let truncated = currency.truncate(amount);
+    if !amount.is_finite() || truncated <= 0.0 {
+        return Err(CryptoNodeError::InvalidInput(format!("Exchange returned an invalid amount {}", amount)));
+    }
+    Ok(truncated)
+}
+
+/// Largest amount that can be sent from `spendable` under a fee model
+fn max_amount(spendable: f64, fee: f64, fee_model: FeeModel) -> f64 {
+    let max = match fee_model {
+        FeeModel::AddedToAmount => spendable - fee,
+        // The recipient must still receive something after the fee
+        FeeModel::DeductedFromAmount if spendable > fee => spendable,
+        FeeModel::DeductedFromAmount => 0.0,
+    };
+    max.max(0.0)
+}
+
+/// Reject a new transaction that `max_sendable` wouldn't allow
+fn check_spendable(spendable: f64, amount: f64, fee: f64, fee_model: FeeModel) -> Result<()> {
+    if fee_model == FeeModel::DeductedFromAmount && amount <= fee {
+        return Err(CryptoNodeError::InvalidInput("Amount must exceed the fee".to_string()));
+    }
+    if amount > max_amount(spendable, fee, fee_model) {
+        return Err(CryptoNodeError::InsufficientBalance(format!(
+            "{} spendable after pending transactions, {} needed", spendable.max(0.0), fee_model.settle(amount, fee).0
+        )));
+    }
+    Ok(())
+}
+
+/// Whether a transaction carries a signature by its sending address
+fn signature_valid(transaction: &Transaction) -> bool {
+    let Some(signature) = &transaction.signature else {
+        return false;
+    };
+    // The sending address is the hex-encoded public key
+    match hex::decode(&transaction.from_wallet) {
+        Ok(public_key) => transaction.signing_payload()
+            .and_then(|payload| crypto::verify_any(&public_key, &payload, signature))
+            .unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+/// Canonical form of a hex address: trimmed and lowercase
+pub fn normalize_address(address: &str) -> String {
+    address.trim().to_ascii_lowercase()
+}
+
+/// Whether two addresses are equal ignoring hex case
+fn same_address(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// Reject a balance that is non-finite or above the cap
+fn check_balance(balance: f64, max: Option<f64>) -> Result<()> {
+    if !balance.is_finite() {
+        return Err(CryptoNodeError::InvalidInput(format!("Balance {} is not finite", balance)));
+    }
+    if let Some(max) = max.filter(|&max| balance > max) {
+        return Err(CryptoNodeError::InvalidInput(format!("Balance {} would exceed the maximum of {}", balance, max)));
+    }
+    Ok(())
+}
+
+/// Whether an address is a wallet's own or one of its derived receive addresses
+fn receives_at(wallet: &Wallet, address: &str) -> bool {
+    same_address(&wallet.address, address)
+        || wallet.receive_addresses.iter().any(|derived| same_address(derived, address))
+}
+
+/// Whether neither side of a transaction is a managed wallet
+fn is_orphaned(transaction: &Transaction, wallets: &HashMap<Uuid, Wallet>) -> bool {
+    !wallets.values().any(|w| {
+        same_address(&w.address, &transaction.from_wallet) || receives_at(w, &transaction.to_wallet)
+    })
+}
+
+/// Create a wallet with keys derived from a secret key
+fn build_wallet(currency_type: CurrencyType, algorithm: SigningAlgorithm, secret_key_bytes: [u8; 32]) -> Result<Wallet> {
+    let public_key = algorithm.public_key(&secret_key_bytes)?;
+
+    Ok(Wallet {
+        id: Uuid::new_v4(),
+        address: hex::encode(&public_key),
+        public_key,
+        private_key: secret_key_bytes.to_vec(),
+        signing_algorithm: algorithm,
+        currency_type,
+        balance: 0.0,
+        created_at: Utc::now(),
+        last_updated: Utc::now(),
+        frozen: false,
+        receive_addresses: Vec::new(),
+    })
+}
+
+/// Verify a signature produced by `WalletManager::sign_message`
+pub fn verify_message(address: &str, message: &[u8], signature: &[u8]) -> Result<bool> {
+    let public_key = hex::decode(address)
+        .map_err(|e| CryptoNodeError::InvalidInput(format!("Invalid address: {}", e)))?;
+    crypto::verify_any(&public_key, &crypto::message_payload(message), signature)
+}
+
+/// Store queued transactions in order until every sender is dropped
+async fn run_transaction_queue(
+    transactions: Arc<RwLock<TransactionStore>>,
+    wallets: Arc<RwLock<HashMap<Uuid, Wallet>>>,
+    mut queue: mpsc::Receiver<QueuedTransaction>,
+) {
+    let mut batch = Vec::with_capacity(QUEUE_BATCH_SIZE);
+    while queue.recv_many(&mut batch, QUEUE_BATCH_SIZE).await > 0 {
+        let mut store = transactions.write().await;
+        let wallets = wallets.read().await;
+        let replies: Vec<_> = batch.drain(..)
+            .map(|queued| (queued.reply, store.admit(&wallets, queued.fee_model, queued.transaction)))
+            .collect();
+        drop(wallets);
+        drop(store);
+
+        for (reply, result) in replies {
+            let _ = reply.send(result);
+        }
+    }
+}
+
+/// Ask the auto-backup task to write now and wait for the result
+async fn flush_via(tx: &mpsc::Sender<BackupSignal>) -> Result<()> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    tx.send(BackupSignal::FlushNow(reply_tx)).await
+        .map_err(|_| CryptoNodeError::Storage("Backup task has stopped".to_string()))?;
+    reply_rx.await
+        .map_err(|_| CryptoNodeError::Storage("Backup task has stopped".to_string()))?
+}
+
+/// Encrypt and write the current wallet state to `path`
+async fn write_backup(
+    path: &Path,
+    passphrase_source: &PassphraseSource,
+    wallets: &RwLock<HashMap<Uuid, Wallet>>,
+    transactions: &RwLock<TransactionStore>,
+) -> Result<()> {
+    let data = WalletBackup {
+        wallets: wallets.read().await.values().cloned().collect(),
+        transactions: transactions.read().await.transactions.clone(),
+        created_at: Utc::now(),
+    };
+
+    // Key derivation and retried file writes block, so keep them off the runtime workers
+    let (path, passphrase_source) = (path.to_path_buf(), passphrase_source.clone());
+    tokio::task::spawn_blocking(move || {
+        let passphrase = passphrase_source.resolve()?;
+        storage::backup(&path, &data, &passphrase)
+    })
+    .await
+    .map_err(|e| CryptoNodeError::Storage(format!("Backup task failed: {}", e)))?
+}
+
+/// Outcome of checking a recovery phrase with `validate_mnemonic`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MnemonicValidation {
+    pub word_count: usize,
+    pub word_count_valid: bool,
+    /// Positions of words that are not in the BIP-39 English wordlist
+    pub invalid_words: Vec<usize>,
+    /// Only true when the word count is valid and every word is known
+    pub checksum_valid: bool,
+}
+
+impl MnemonicValidation {
+    /// Whether the phrase can be used for recovery
+    pub fn is_valid(&self) -> bool {
+        self.word_count_valid && self.invalid_words.is_empty() && self.checksum_valid
+    }
+}
+
+/// Check a BIP-39 English recovery phrase without creating a wallet
+///
+/// Reports each unknown word separately so a UI can highlight it.
+pub fn validate_mnemonic(phrase: &str) -> Result<MnemonicValidation> {
+    let words: Vec<String> = phrase.split_whitespace().map(str::to_lowercase).collect();
+    if words.is_empty() {
+        return Err(CryptoNodeError::InvalidInput("Recovery phrase is empty".to_string()));
+    }
+
+    let language = bip39::Language::English;
+    let invalid_words: Vec<usize> = words.iter()
+        .enumerate()
+        .filter(|(_, word)| language.find_word(word).is_none())
+        .map(|(i, _)| i)
+        .collect();
+    let word_count_valid = words.len().is_multiple_of(3) && (12..=24).contains(&words.len());
+
+    let checksum_valid = word_count_valid
+        && invalid_words.is_empty()
+        && bip39::Mnemonic::parse_in_normalized(language, &words.join(" ")).is_ok();
+
+    Ok(MnemonicValidation {
+        word_count: words.len(),
+        word_count_valid,
+        invalid_words,
+        checksum_valid,
+    })
+}
+
+/// Record a status transition in the log trail for a transaction
+///
+/// Amounts are left out; the currency is enough to tell transactions apart
+/// when debugging.
+fn log_transition(transaction: &Transaction, previous: TransactionStatus, trigger: &str) {
+    if transaction.status == previous {
+        return;
+    }
+    info!(
+        transaction_id = %transaction.id,
+        from = ?previous,
+        to = ?transaction.status,
+        currency = transaction.currency_type.symbol(),
+        trigger,
+        at = %Utc::now(),
+        "Transaction status changed"
+    );
+}
+
+/// Rank used to resolve status conflicts when merging histories
+fn status_precedence(status: TransactionStatus) -> u8 {
+    match status {
+        TransactionStatus::Pending => 0,
+        TransactionStatus::Failed => 1,
+        TransactionStatus::Confirmed => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::NetworkFailure;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cryptonode-wallet-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn passphrase_file(dir: &Path) -> PassphraseSource {
+        let path = dir.join("passphrase");
+        std::fs::write(&path, "correct horse battery staple").unwrap();
+        PassphraseSource::File(path)
+    }
+
+    /// A manager holding one funded wallet
+    async fn funded_wallet(balance: f64) -> (WalletManager, Wallet) {
+        let manager = WalletManager::new();
+        let wallet = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let wallet = manager.update_wallet_balance(wallet.id, balance).await.unwrap();
+        (manager, wallet)
+    }
+
+    /// Two funded wallets of the same currency with a pending A to B transaction
+    async fn pending_transfer() -> (WalletManager, Wallet, Wallet, Transaction) {
+        let (manager, sender) = funded_wallet(10.0).await;
+        let recipient = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let tx = manager.create_transaction(&sender, recipient.address.clone(), 1.0).await.unwrap();
+        (manager, sender, recipient, tx)
+    }
+
+    fn transaction(status: TransactionStatus) -> Transaction {
+        Transaction {
+            id: Uuid::new_v4(),
+            from_wallet: "aa".to_string(),
+            to_wallet: "bb".to_string(),
+            amount: 1.0,
+            currency_type: CurrencyType::Bitcoin,
+            timestamp: Utc::now(),
+            status,
+            fee: None,
+            signature: None,
+            category: None,
+        }
+    }
+
+    /// Build a 1.0 transfer and sign its payload with the sender's key, as an external device would
+    async fn externally_signed() -> (WalletManager, Wallet, UnsignedTransaction, Vec<u8>) {
+        let (manager, sender) = funded_wallet(10.0).await;
+        let recipient = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let unsigned = manager.build_unsigned_transaction(&sender, recipient.address, 1.0).unwrap();
+        let signature = sender.signing_algorithm.sign(&sender.private_key, &unsigned.payload).unwrap();
+        (manager, sender, unsigned, signature)
+    }
+
+    #[tokio::test]
+    async fn attached_signature_is_recorded_as_pending() {
+        let (manager, sender, mut unsigned, signature) = externally_signed().await;
+        // Status is outside the signed payload, so a claimed confirmation must be ignored
+        unsigned.transaction.status = TransactionStatus::Confirmed;
+
+        let tx = manager.attach_signature(unsigned, &signature, &sender.public_key).await.unwrap();
+
+        assert_eq!(tx.status, TransactionStatus::Pending);
+        assert_eq!(manager.find_transaction(tx.id).await.unwrap().status, TransactionStatus::Pending);
+        assert_eq!(manager.get_wallet(sender.id).await.unwrap().balance, 10.0);
+        assert!(manager.verify_transaction(&tx, &sender.public_key).unwrap());
+    }
+
+    #[tokio::test]
+    async fn attach_rejects_tampered_payload() {
+        let (manager, sender, mut unsigned, signature) = externally_signed().await;
+        unsigned.transaction.amount = 2.0;
+
+        let result = manager.attach_signature(unsigned, &signature, &sender.public_key).await;
+        assert!(matches!(result, Err(CryptoNodeError::Security(_))));
+    }
+
+    #[tokio::test]
+    async fn attach_rejects_signature_from_another_key() {
+        let (manager, sender, unsigned, _) = externally_signed().await;
+        let other = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let forged = other.signing_algorithm.sign(&other.private_key, &unsigned.payload).unwrap();
+
+        let result = manager.attach_signature(unsigned.clone(), &forged, &other.public_key).await;
+        assert!(matches!(result, Err(CryptoNodeError::Security(_))));
+        let result = manager.attach_signature(unsigned, &forged, &sender.public_key).await;
+        assert!(matches!(result, Err(CryptoNodeError::Security(_))));
+    }
+
+    #[tokio::test]
+    async fn attach_rejects_replay() {
+        let (manager, sender, unsigned, signature) = externally_signed().await;
+        manager.attach_signature(unsigned.clone(), &signature, &sender.public_key).await.unwrap();
+
+        let result = manager.attach_signature(unsigned, &signature, &sender.public_key).await;
+        assert!(matches!(result, Err(CryptoNodeError::InvalidInput(_))));
+        assert_eq!(manager.get_transaction_history(&sender.address).await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn history_is_indexed_by_both_addresses() {
+        let mut store = TransactionStore::default();
+        let outgoing = transaction(TransactionStatus::Pending);
+        let incoming = Transaction { from_wallet: "cc".to_string(), to_wallet: "aa".to_string(), ..transaction(TransactionStatus::Pending) };
+        let unrelated = Transaction { from_wallet: "cc".to_string(), to_wallet: "dd".to_string(), ..transaction(TransactionStatus::Pending) };
+        for tx in [&outgoing, &incoming, &unrelated] {
+            store.push(tx.clone());
+        }
+
+        let ids: Vec<Uuid> = store.history("aa").iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![outgoing.id, incoming.id]);
+        assert_eq!(store.history("bb").len(), 1);
+        assert!(store.history("ee").is_empty());
+    }
+
+    #[test]
+    fn self_transfer_appears_once_in_history() {
+        let mut store = TransactionStore::default();
+        store.push(Transaction { to_wallet: "aa".to_string(), ..transaction(TransactionStatus::Pending) });
+        assert_eq!(store.history("aa").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn history_reads_share_the_store_lock() {
+        let (manager, sender, _, tx) = pending_transfer().await;
+        let _reader = manager.transactions.read().await;
+
+        let history = timeout(Duration::from_secs(1), manager.get_transaction_history(&sender.address))
+            .await
+            .expect("history read blocked behind another reader")
+            .unwrap();
+        assert_eq!(history[0].id, tx.id);
+    }
+
+    #[tokio::test]
+    async fn signature_audit_flags_only_tampered_transactions() {
+        let (manager, sender, recipient, tampered) = pending_transfer().await;
+        let intact = manager.create_transaction(&sender, recipient.address.clone(), 2.0).await.unwrap();
+        assert!(manager.verify_all_signatures().await.unwrap().is_empty());
+
+        {
+            let mut transactions = manager.transactions.write().await;
+            transactions.transactions.iter_mut().find(|t| t.id == tampered.id).unwrap().amount = 5.0;
+            // Unsigned transactions have nothing to verify and are skipped
+            transactions.push(transaction(TransactionStatus::Pending));
+        }
+
+        let failed = manager.verify_all_signatures().await.unwrap();
+        assert_eq!(failed, vec![tampered.id]);
+        assert!(!failed.contains(&intact.id));
+    }
+
+    #[tokio::test]
+    async fn frozen_wallet_cannot_send_until_unfrozen() {
+        let (manager, sender) = funded_wallet(10.0).await;
+        let recipient = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let frozen = manager.freeze_wallet(sender.id).await.unwrap();
+        assert!(frozen.frozen);
+
+        let result = manager.create_transaction(&frozen, recipient.address.clone(), 1.0).await;
+        assert!(matches!(result, Err(CryptoNodeError::PermissionDenied(_))));
+        assert!(matches!(manager.sign_message(sender.id, b"hello").await, Err(CryptoNodeError::PermissionDenied(_))));
+
+        let unfrozen = manager.unfreeze_wallet(sender.id).await.unwrap();
+        assert!(manager.create_transaction(&unfrozen, recipient.address, 1.0).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn frozen_wallet_still_receives_and_answers_queries() {
+        let (manager, sender, recipient, tx) = pending_transfer().await;
+        manager.freeze_wallet(recipient.id).await.unwrap();
+
+        manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await.unwrap();
+        let recipient = manager.get_wallet(recipient.id).await.unwrap();
+        assert!(recipient.frozen);
+        assert_eq!(recipient.balance, 1.0);
+        assert_eq!(manager.get_transaction_history(&recipient.address).await.unwrap().len(), 1);
+        assert!(manager.get_wallet(sender.id).await.unwrap().balance < 10.0);
+    }
+
+    /// Address reuse warnings already waiting on `events`
+    fn reuse_warnings(events: &mut broadcast::Receiver<WalletEvent>) -> Vec<Uuid> {
+        let mut warnings = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let WalletEvent::AddressReuseWarning { transaction_id, .. } = event {
+                warnings.push(transaction_id);
+            }
+        }
+        warnings
+    }
+
+    #[tokio::test]
+    async fn reused_recipient_address_warns_but_sends() {
+        let (manager, sender) = funded_wallet(10.0).await;
+        let mut events = manager.subscribe();
+
+        let first = manager.create_transaction(&sender, "cafe".to_string(), 1.0).await.unwrap();
+        assert!(reuse_warnings(&mut events).is_empty());
+
+        // Addresses compare case-insensitively, so this is the same recipient
+        let second = manager.create_transaction(&sender, "CAFE".to_string(), 1.0).await.unwrap();
+        assert_eq!(reuse_warnings(&mut events), vec![second.id]);
+        assert_ne!(first.id, second.id);
+        assert_eq!(manager.get_transaction_history("cafe").await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn restore_accepts_watch_only_wallets() {
+        let (manager, wallet) = funded_wallet(1.0).await;
+        let mut snapshot = manager.snapshot().await;
+        snapshot.wallets.get_mut(&wallet.id).unwrap().private_key.clear();
+
+        let restored = WalletManager::new();
+        restored.restore(snapshot).await.unwrap();
+        assert!(restored.get_wallet(wallet.id).await.unwrap().private_key.is_empty());
+    }
+
+    #[tokio::test]
+    async fn restore_rejects_invalid_key() {
+        let (manager, wallet) = funded_wallet(1.0).await;
+        let mut snapshot = manager.snapshot().await;
+        snapshot.wallets.get_mut(&wallet.id).unwrap().private_key = vec![1, 2, 3];
+
+        assert!(WalletManager::new().restore(snapshot).await.is_err());
+    }
+
+    /// Approves transactions up to a limit and remembers what it was asked
+    struct LimitApprover {
+        limit: f64,
+        seen: std::sync::Mutex<Vec<Uuid>>,
+    }
+
+    impl TransactionApprover for LimitApprover {
+        fn approve(&self, tx: &Transaction) -> Result<bool> {
+            self.seen.lock().unwrap().push(tx.id);
+            Ok(tx.amount <= self.limit)
+        }
+    }
+
+    #[tokio::test]
+    async fn approver_decides_before_transactions_are_recorded() {
+        let (manager, sender) = funded_wallet(10.0).await;
+        let approver = Arc::new(LimitApprover { limit: 2.0, seen: std::sync::Mutex::new(Vec::new()) });
+        manager.set_approver(approver.clone()).await;
+
+        let approved = manager.create_transaction(&sender, "cafe".to_string(), 1.0).await.unwrap();
+        let denied = manager.create_transaction(&sender, "cafe".to_string(), 3.0).await;
+
+        assert!(matches!(denied, Err(CryptoNodeError::PermissionDenied(_))));
+        assert_eq!(approver.seen.lock().unwrap().len(), 2);
+        let history = manager.get_transaction_history(&sender.address).await.unwrap();
+        assert_eq!(history.iter().map(|t| t.id).collect::<Vec<_>>(), vec![approved.id]);
+    }
+
+    #[tokio::test]
+    async fn amounts_beyond_currency_precision_are_rejected() {
+        let (manager, sender) = funded_wallet(10.0).await;
+
+        let result = manager.create_transaction(&sender, "cafe".to_string(), 1.000000001).await;
+
+        assert!(matches!(result, Err(CryptoNodeError::InvalidInput(_))));
+        assert!(manager.get_transaction_history(&sender.address).await.unwrap().is_empty());
+        assert!(manager.create_transaction(&sender, "cafe".to_string(), 1.00000001).await.is_ok());
+    }
+
+    #[test]
+    fn cap_evicts_oldest_failed_transactions() {
+        let mut store = TransactionStore { max_transactions: Some(3), ..TransactionStore::default() };
+        let failed: Vec<Transaction> = (0..2).map(|_| transaction(TransactionStatus::Failed)).collect();
+        for tx in &failed {
+            store.record(tx.clone());
+        }
+        let kept: Vec<Transaction> = (0..2).map(|_| transaction(TransactionStatus::Confirmed)).collect();
+        for tx in &kept {
+            store.record(tx.clone());
+        }
+
+        assert_eq!(store.transactions.len(), 3);
+        assert!(store.get(failed[0].id).is_none());
+        assert!(store.get(failed[1].id).is_some());
+        assert_eq!(store.get(kept[1].id).map(|t| t.id), Some(kept[1].id));
+        assert_eq!(store.history("aa").len(), 3);
+    }
+
+    #[test]
+    fn cap_keeps_pending_and_confirmed_when_nothing_is_evictable() {
+        let mut store = TransactionStore { max_transactions: Some(1), ..TransactionStore::default() };
+        let pending = transaction(TransactionStatus::Pending);
+        store.record(pending.clone());
+        store.record(transaction(TransactionStatus::Confirmed));
+
+        assert_eq!(store.transactions.len(), 2);
+        assert!(store.get(pending.id).is_some());
+    }
+
+    /// Estimates a fixed minute per unit sent
+    struct MinutePerUnit;
+
+    impl ConfirmationEstimator for MinutePerUnit {
+        fn estimate(&self, tx: &Transaction) -> Result<Option<Duration>> {
+            Ok(Some(Duration::from_secs(60 * tx.amount as u64)))
+        }
+    }
+
+    #[tokio::test]
+    async fn confirmation_estimate_uses_the_configured_estimator() {
+        let (manager, _, _, tx) = pending_transfer().await;
+        assert_eq!(manager.estimate_confirmation_time(tx.id).await.unwrap(), None);
+
+        manager.set_confirmation_estimator(Some(Arc::new(MinutePerUnit))).await;
+        assert_eq!(manager.estimate_confirmation_time(tx.id).await.unwrap(), Some(Duration::from_secs(60)));
+
+        manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await.unwrap();
+        assert_eq!(manager.estimate_confirmation_time(tx.id).await.unwrap(), None);
+        assert!(matches!(
+            manager.estimate_confirmation_time(Uuid::new_v4()).await,
+            Err(CryptoNodeError::NotFound(_))
+        ));
+    }
+
+    fn token(contract: &str) -> CurrencyType {
+        CurrencyType::Token {
+            contract: contract.to_string(),
+            symbol: "TOK".to_string(),
+            name: "Token".to_string(),
+            decimals: 6,
+        }
+    }
+
+    #[tokio::test]
+    async fn currency_history_matches_the_token_contract() {
+        let manager = WalletManager::new();
+        let bitcoin = transaction(TransactionStatus::Confirmed);
+        let first = Transaction { id: Uuid::new_v4(), currency_type: token("0x01"), ..bitcoin.clone() };
+        let second = Transaction { id: Uuid::new_v4(), currency_type: token("0x02"), ..bitcoin.clone() };
+        {
+            let mut transactions = manager.transactions.write().await;
+            for tx in [&bitcoin, &first, &second] {
+                transactions.record(tx.clone());
+            }
+        }
+
+        let history = manager.get_transaction_history_by_currency("aa", &token("0x01")).await.unwrap();
+        assert_eq!(history.iter().map(|t| t.id).collect::<Vec<_>>(), vec![first.id]);
+        let history = manager.get_transaction_history_by_currency("bb", &CurrencyType::Bitcoin).await.unwrap();
+        assert_eq!(history.iter().map(|t| t.id).collect::<Vec<_>>(), vec![bitcoin.id]);
+        assert!(manager.get_transaction_history_by_currency("cc", &CurrencyType::Bitcoin).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn tokens_without_contract_or_symbol_cannot_get_wallets() {
+        let manager = WalletManager::new();
+        let no_contract = CurrencyType::Token {
+            contract: " ".to_string(),
+            symbol: "TOK".to_string(),
+            name: "Token".to_string(),
+            decimals: 6,
+        };
+        let no_symbol = CurrencyType::Token {
+            contract: "0x01".to_string(),
+            symbol: String::new(),
+            name: "Token".to_string(),
+            decimals: 6,
+        };
+
+        assert!(matches!(manager.create_wallet(no_contract).await, Err(CryptoNodeError::InvalidInput(_))));
+        assert!(matches!(manager.create_wallet(no_symbol).await, Err(CryptoNodeError::InvalidInput(_))));
+        assert!(manager.list_wallets().await.unwrap().is_empty());
+        assert!(manager.create_wallet(token("0x01")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn wallet_pages_cover_every_wallet_once() {
+        let manager = WalletManager::new();
+        for _ in 0..3 {
+            manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        }
+
+        let first = manager.list_wallets_page(0, 2).await.unwrap();
+        let second = manager.list_wallets_page(2, 2).await.unwrap();
+
+        assert_eq!((first.total, first.has_more, second.has_more), (3, true, false));
+        let mut ids: Vec<Uuid> = first.items.iter().chain(&second.items).map(|w| w.id).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn safe_mode_blocks_sends_but_not_wallet_tracking() {
+        let (manager, sender) = funded_wallet(10.0).await;
+        manager.set_safe_mode(true);
+
+        let result = manager.create_transaction(&sender, "cafe".to_string(), 1.0).await;
+        assert!(matches!(result, Err(CryptoNodeError::PermissionDenied(_))));
+        assert!(manager.create_wallet(CurrencyType::Bitcoin).await.is_ok());
+        assert_eq!(manager.update_wallet_balance(sender.id, 12.0).await.unwrap().balance, 12.0);
+
+        manager.set_safe_mode(false);
+        assert!(manager.create_transaction(&sender, "cafe".to_string(), 1.0).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn safe_mode_follows_config_changes() {
+        let manager = WalletManager::new();
+        let (config, receiver) = watch::channel(DeviceConfig::default());
+        manager.follow_config(receiver);
+
+        config.send_modify(|c| c.safe_mode = true);
+        timeout(Duration::from_secs(1), async {
+            while !manager.is_safe_mode() {
+                tokio::task::yield_now().await;
+            }
+        }).await.expect("safe mode never enabled");
+
+        config.send_modify(|c| c.safe_mode = false);
+        timeout(Duration::from_secs(1), async {
+            while manager.is_safe_mode() {
+                tokio::task::yield_now().await;
+            }
+        }).await.expect("safe mode never disabled");
+    }
+
+    #[tokio::test]
+    async fn merge_resolves_conflict_and_applies_balances() {
+        let (manager, sender, recipient, tx) = pending_transfer().await;
+        let remote = Transaction { status: TransactionStatus::Confirmed, ..tx.clone() };
+
+        let report = manager.merge_history(vec![remote]).await.unwrap();
+        assert_eq!(report.conflicts, vec![MergeConflict {
+            transaction_id: tx.id,
+            local: TransactionStatus::Pending,
+            remote: TransactionStatus::Confirmed,
+            resolved: TransactionStatus::Confirmed,
+        }]);
+        assert!(report.rejected.is_empty());
+
+        let fee = tx.fee.unwrap();
+        assert_eq!(manager.get_wallet(sender.id).await.unwrap().balance, 10.0 - 1.0 - fee);
+        assert_eq!(manager.get_wallet(recipient.id).await.unwrap().balance, 1.0);
+
+        // Confirming again must not apply the balances twice
+        manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await.unwrap();
+        assert_eq!(manager.get_wallet(recipient.id).await.unwrap().balance, 1.0);
+    }
+
+    #[tokio::test]
+    async fn merge_keeps_higher_precedence_local_status() {
+        let (manager, _, _, tx) = pending_transfer().await;
+        manager.update_transaction_status(tx.id, TransactionStatus::Failed).await.unwrap();
+
+        let report = manager.merge_history(vec![tx.clone()]).await.unwrap();
+        assert_eq!(report.conflicts[0].resolved, TransactionStatus::Failed);
+        assert_eq!(manager.find_transaction(tx.id).await.unwrap().status, TransactionStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn merge_adds_unknown_transactions() {
+        let (source, _, recipient, tx) = pending_transfer().await;
+        let manager = WalletManager::new();
+        manager.restore(WalletSnapshot {
+            wallets: HashMap::from([(recipient.id, recipient.clone())]),
+            transactions: Vec::new(),
+            redacted: false,
+        }).await.unwrap();
+        drop(source);
+
+        let remote = Transaction { status: TransactionStatus::Confirmed, ..tx.clone() };
+        let report = manager.merge_history(vec![remote]).await.unwrap();
+        assert_eq!(report.added, 1);
+        assert_eq!(manager.find_transaction(tx.id).await.unwrap().status, TransactionStatus::Confirmed);
+        assert_eq!(manager.get_wallet(recipient.id).await.unwrap().balance, 1.0);
+    }
+
+    #[tokio::test]
+    async fn merge_rejects_tampered_signature() {
+        let (manager, _, _, tx) = pending_transfer().await;
+        let tampered = Transaction { amount: 5.0, status: TransactionStatus::Confirmed, ..tx.clone() };
+
+        assert!(matches!(manager.merge_history(vec![tampered]).await, Err(CryptoNodeError::Security(_))));
+        assert_eq!(manager.find_transaction(tx.id).await.unwrap().status, TransactionStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn merge_rejects_unsigned_transfers_from_local_wallets() {
+        let (manager, sender) = funded_wallet(10.0).await;
+        let forged = Transaction {
+            from_wallet: sender.address.clone(),
+            ..transaction(TransactionStatus::Confirmed)
+        };
+
+        let result = manager.merge_history(vec![forged.clone()]).await;
+        assert!(matches!(result, Err(CryptoNodeError::Security(_))));
+        assert_eq!(manager.get_wallet(sender.id).await.unwrap().balance, 10.0);
+        assert!(manager.find_transaction(forged.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn fingerprint_is_short_stable_and_not_the_address() {
+        let manager = WalletManager::new();
+        let wallet = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let other = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+
+        let fingerprint = wallet.fingerprint();
+        assert_eq!(fingerprint.len(), 8);
+        assert!(fingerprint.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(manager.get_wallet(wallet.id).await.unwrap().fingerprint(), fingerprint);
+        assert_ne!(other.fingerprint(), fingerprint);
+        assert!(!wallet.address.contains(&fingerprint));
+    }
+
+    #[tokio::test]
+    async fn attention_flags_each_condition_per_wallet() {
+        let (manager, low) = funded_wallet(1.0).await;
+        let busy = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let busy = manager.update_wallet_balance(busy.id, 10.0).await.unwrap();
+        manager.set_attention_thresholds(AttentionThresholds { low_balance: 5.0, ..AttentionThresholds::default() }).await;
+        {
+            let mut transactions = manager.transactions.write().await;
+            transactions.record(Transaction {
+                from_wallet: busy.address.clone(),
+                timestamp: Utc::now() - chrono::Duration::hours(2),
+                ..transaction(TransactionStatus::Pending)
+            });
+            transactions.record(Transaction {
+                from_wallet: low.address.clone(),
+                ..transaction(TransactionStatus::Failed)
+            });
+            transactions.record(Transaction {
+                from_wallet: busy.address.clone(),
+                timestamp: Utc::now() - chrono::Duration::days(2),
+                ..transaction(TransactionStatus::Failed)
+            });
+        }
+
+        let items = manager.wallets_needing_attention().await;
+
+        assert_eq!(items.len(), 3);
+        for expected in [
+            AttentionItem { wallet_id: low.id, reason: AttentionReason::LowBalance },
+            AttentionItem { wallet_id: low.id, reason: AttentionReason::RecentFailure },
+            AttentionItem { wallet_id: busy.id, reason: AttentionReason::StalePendingTransaction },
+        ] {
+            assert!(items.contains(&expected), "missing {:?}", expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn confirmation_rejected_when_sender_is_short() {
+        let (manager, sender, recipient, tx) = pending_transfer().await;
+        manager.update_wallet_balance(sender.id, 0.5).await.unwrap();
+
+        assert!(manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await.is_err());
+        assert_eq!(manager.find_transaction(tx.id).await.unwrap().status, TransactionStatus::Pending);
+        assert_eq!(manager.get_wallet(sender.id).await.unwrap().balance, 0.5);
+        assert_eq!(manager.get_wallet(recipient.id).await.unwrap().balance, 0.0);
+    }
+
+    #[tokio::test]
+    async fn fee_models_settle_confirmed_transactions() {
+        for (model, expected_debit, expected_credit) in [
+            (FeeModel::AddedToAmount, 1.0 + ESTIMATED_FEE, 1.0),
+            (FeeModel::DeductedFromAmount, 1.0, 1.0 - ESTIMATED_FEE),
+        ] {
+            let (manager, sender) = funded_wallet(10.0).await;
+            manager.set_fee_model(CurrencyType::Bitcoin, model).await;
+            let recipient = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+            let tx = manager.create_transaction(&sender, recipient.address.clone(), 1.0).await.unwrap();
+            manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await.unwrap();
+
+            let debit = 10.0 - manager.get_wallet(sender.id).await.unwrap().balance;
+            let credit = manager.get_wallet(recipient.id).await.unwrap().balance;
+            assert!((debit - expected_debit).abs() < 1e-9, "{:?} debit {}", model, debit);
+            assert!((credit - expected_credit).abs() < 1e-9, "{:?} credit {}", model, credit);
+        }
+    }
+
+    #[tokio::test]
+    async fn sweep_below_fee_is_insufficient_balance() {
+        let (manager, wallet) = funded_wallet(ESTIMATED_FEE / 2.0).await;
+        let result = manager.sweep(wallet.id, "abcd").await;
+        assert!(matches!(result, Err(CryptoNodeError::InsufficientBalance(_))));
+    }
+
+    #[tokio::test]
+    async fn pending_debits_count_against_new_transactions() {
+        let (manager, sender, recipient, tx) = pending_transfer().await;
+        let spendable = 10.0 - 1.0 - tx.fee.unwrap();
+        assert_eq!(manager.max_sendable(sender.id).await.unwrap(), sender.currency_type.truncate(spendable - tx.fee.unwrap()));
+
+        let result = manager.create_transaction(&sender, recipient.address.clone(), spendable).await;
+        assert!(matches!(result, Err(CryptoNodeError::InsufficientBalance(_))));
+
+        let max = manager.max_sendable(sender.id).await.unwrap();
+        assert!(manager.create_transaction(&sender, recipient.address.clone(), max).await.is_ok());
+        assert_eq!(manager.max_sendable(sender.id).await.unwrap(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn sweep_sends_max_sendable() {
+        let (manager, wallet) = funded_wallet(3.0).await;
+        let expected = manager.max_sendable(wallet.id).await.unwrap();
+        let tx = manager.sweep(wallet.id, "abcd").await.unwrap();
+        assert_eq!(tx.amount, expected);
+    }
+
+    #[tokio::test]
+    async fn detailed_creation_returns_the_recovery_phrase_for_the_key() {
+        let manager = WalletManager::new();
+
+        let created = manager.create_wallet_detailed(CurrencyType::Bitcoin, true).await.unwrap();
+
+        let phrase = bip39::Mnemonic::parse(created.mnemonic.as_deref().unwrap()).unwrap();
+        let stored = manager.get_wallet(created.wallet.id).await.unwrap();
+        assert_eq!(phrase.word_count(), 24);
+        assert_eq!(phrase.to_entropy(), stored.private_key);
+        assert_eq!(created.warnings, vec!["Automatic backups are disabled".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn detailed_creation_warns_without_a_recovery_phrase() {
+        let manager = WalletManager::new();
+        manager.set_safe_mode(true);
+
+        let created = manager.create_wallet_detailed(CurrencyType::Bitcoin, false).await.unwrap();
+
+        assert!(created.mnemonic.is_none());
+        assert_eq!(created.warnings.len(), 3);
+        assert!(created.warnings[0].starts_with("No recovery phrase"));
+        assert!(created.warnings[2].starts_with("Safe mode is active"));
+    }
+
+    #[tokio::test]
+    async fn find_transaction_returns_the_current_record() {
+        let (manager, _, _, tx) = pending_transfer().await;
+        assert_eq!(manager.find_transaction(tx.id).await.unwrap().status, TransactionStatus::Pending);
+
+        manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await.unwrap();
+
+        assert_eq!(manager.find_transaction(tx.id).await.unwrap().status, TransactionStatus::Confirmed);
+        assert!(matches!(manager.find_transaction(Uuid::new_v4()).await, Err(CryptoNodeError::NotFound(_))));
+    }
+
+    fn balance_changes(events: &mut broadcast::Receiver<WalletEvent>) -> Vec<(Uuid, f64, f64, BalanceChangeReason)> {
+        let mut changes = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let WalletEvent::BalanceChanged { wallet_id, old_balance, new_balance, reason } = event {
+                changes.push((wallet_id, old_balance, new_balance, reason));
+            }
+        }
+        changes
+    }
+
+    #[tokio::test]
+    async fn balance_changes_carry_their_reason() {
+        let (manager, sender, recipient, tx) = pending_transfer().await;
+        let mut events = manager.subscribe();
+
+        manager.update_wallet_balance_with_reason(sender.id, 12.0, BalanceChangeReason::ExternalSync).await.unwrap();
+        manager.credit_wallet(recipient.id, 0.5, BalanceChangeReason::Reward).await.unwrap();
+        manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await.unwrap();
+        manager.update_wallet_balance(recipient.id, 3.0).await.unwrap();
+
+        let mut changes = balance_changes(&mut events);
+        let manual = changes.pop().unwrap();
+        assert_eq!(manual, (recipient.id, 1.5, 3.0, BalanceChangeReason::Manual));
+        assert_eq!(changes[..2], [
+            (sender.id, 10.0, 12.0, BalanceChangeReason::ExternalSync),
+            (recipient.id, 0.0, 0.5, BalanceChangeReason::Reward),
+        ]);
+        let confirmed = &changes[2..];
+        assert_eq!(confirmed.len(), 2);
+        // The sender's debit includes the fee, if any
+        assert!(confirmed.iter().any(|&(id, old, new, reason)| {
+            id == sender.id && old == 12.0 && new <= 11.0 && reason == BalanceChangeReason::Transaction
+        }));
+        assert!(confirmed.contains(&(recipient.id, 0.5, 1.5, BalanceChangeReason::Transaction)));
+    }
+
+    #[test]
+    fn mnemonic_validation_reports_words_and_checksum() {
+        let valid = validate_mnemonic(&format!("{} About", "abandon ".repeat(11))).unwrap();
+        assert!(valid.checksum_valid && valid.word_count_valid && valid.invalid_words.is_empty());
+
+        let bad_checksum = validate_mnemonic(&"abandon ".repeat(12)).unwrap();
+        assert!(bad_checksum.word_count_valid);
+        assert!(!bad_checksum.checksum_valid);
+
+        let typo = validate_mnemonic(&format!("abandon abandn {}", "abandon ".repeat(9))).unwrap();
+        assert_eq!(typo.invalid_words, vec![1]);
+        assert_eq!(typo.word_count, 11);
+        assert!(!typo.word_count_valid && !typo.checksum_valid);
+
+        assert!(matches!(validate_mnemonic("  "), Err(CryptoNodeError::InvalidInput(_))));
+    }
+
+    /// Auto-backup to a fresh directory, flushing only on `max_batch` or explicit flushes
+    async fn batched_backups(max_batch: usize) -> (WalletManager, PathBuf, PassphraseSource) {
+        let dir = temp_dir();
+        let source = passphrase_file(&dir);
+        let path = dir.join("wallets.backup");
+        let manager = WalletManager::new();
+        manager.configure_auto_backup(AutoBackupConfig {
+            enabled: true,
+            path: path.clone(),
+            passphrase_source: source.clone(),
+            debounce_secs: 60,
+            max_batch,
+            max_delay_secs: 60,
+        }).await;
+        (manager, path, source)
+    }
+
+    #[tokio::test]
+    async fn backup_is_written_once_the_batch_fills() {
+        let (manager, path, source) = batched_backups(3).await;
+
+        for _ in 0..2 {
+            manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!path.exists());
+
+        manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        timeout(Duration::from_secs(2), async {
+            while !path.exists() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.expect("backup never written");
+
+        let restored = storage::restore_backup(&path, &source.resolve().unwrap()).unwrap();
+        assert_eq!(restored.wallets.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn shutdown_flushes_and_then_refuses_changes() {
+        let (manager, path, source) = batched_backups(100).await;
+        manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        manager.flush_now().await.unwrap();
+        assert_eq!(storage::restore_backup(&path, &source.resolve().unwrap()).unwrap().wallets.len(), 1);
+
+        manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        manager.shutdown().await.unwrap();
+
+        assert_eq!(storage::restore_backup(&path, &source.resolve().unwrap()).unwrap().wallets.len(), 2);
+        assert!(matches!(manager.create_wallet(CurrencyType::Bitcoin).await, Err(CryptoNodeError::Cancelled)));
+        assert!(matches!(manager.flush_now().await, Err(CryptoNodeError::Storage(_))));
+        assert_eq!(manager.list_wallets().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn reward_credits_fill_the_backup_batch() {
+        let (manager, path, source) = batched_backups(3).await;
+        let wallet = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        manager.credit_wallet(wallet.id, 2.0, BalanceChangeReason::Reward).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!path.exists());
+
+        manager.credit_wallet(wallet.id, 3.0, BalanceChangeReason::Reward).await.unwrap();
+        timeout(Duration::from_secs(2), async {
+            while !path.exists() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.expect("backup never written");
+
+        let restored = storage::restore_backup(&path, &source.resolve().unwrap()).unwrap();
+        assert_eq!(restored.wallets[0].balance, 5.0);
+    }
+
+    #[tokio::test]
+    async fn backup_round_trips_through_disk() {
+        let dir = temp_dir();
+        let path = dir.join("wallets.dat");
+        let (manager, wallet) = funded_wallet(2.5).await;
+        let source = passphrase_file(&dir);
+        write_backup(&path, &source, &manager.wallets, &manager.transactions).await.unwrap();
+
+        let restored = storage::restore_backup(&path, &source.resolve().unwrap()).unwrap();
+        assert_eq!(restored.wallets.len(), 1);
+        assert_eq!(restored.wallets[0].private_key, wallet.private_key);
+        assert_eq!(restored.wallets[0].balance, 2.5);
+    }
+
+    #[tokio::test]
+    async fn corrupt_backup_is_rejected() {
+        let dir = temp_dir();
+        let path = dir.join("wallets.dat");
+        let (manager, _) = funded_wallet(1.0).await;
+        let source = passphrase_file(&dir);
+        write_backup(&path, &source, &manager.wallets, &manager.transactions).await.unwrap();
+
+        let mut contents = std::fs::read(&path).unwrap();
+        let last = contents.len() - 1;
+        contents[last] ^= 0xff;
+        std::fs::write(&path, contents).unwrap();
+
+        assert!(storage::restore_backup(&path, &source.resolve().unwrap()).is_err());
+    }
+
+    #[tokio::test]
+    async fn signed_messages_verify_against_the_address() {
+        let manager = WalletManager::new();
+        for algorithm in [SigningAlgorithm::Ed25519, SigningAlgorithm::Secp256k1] {
+            let wallet = manager.create_wallet_with_algorithm(CurrencyType::Bitcoin, algorithm).await.unwrap();
+
+            let signature = manager.sign_message(wallet.id, b"login challenge 42").await.unwrap();
+
+            assert!(verify_message(&wallet.address, b"login challenge 42", &signature).unwrap());
+            assert!(!verify_message(&wallet.address, b"login challenge 43", &signature).unwrap());
+            // The domain prefix keeps a raw signature from passing as a message signature
+            let raw = algorithm.sign(&wallet.private_key, b"login challenge 42").unwrap();
+            assert!(!verify_message(&wallet.address, b"login challenge 42", &raw).unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn frozen_wallets_cannot_sign_messages() {
+        let (manager, wallet) = funded_wallet(1.0).await;
+        manager.freeze_wallet(wallet.id).await.unwrap();
+
+        assert!(matches!(manager.sign_message(wallet.id, b"hello").await, Err(CryptoNodeError::PermissionDenied(_))));
+    }
+
+    #[tokio::test]
+    async fn wallets_in_unknown_currencies_load_but_cannot_send() {
+        let (manager, wallet) = funded_wallet(5.0).await;
+        let placeholder = Wallet { currency_type: CurrencyType::Unknown("Solana".to_string()), ..wallet.clone() };
+        manager.wallets.write().await.insert(wallet.id, placeholder.clone());
+
+        assert_eq!(manager.get_wallet(wallet.id).await.unwrap().balance, 5.0);
+        let result = manager.create_transaction(&placeholder, "cafe".to_string(), 1.0).await;
+        assert!(matches!(result, Err(CryptoNodeError::NotImplemented(_))));
+    }
+
+    #[tokio::test]
+    async fn categories_label_transactions_without_breaking_signatures() {
+        let (manager, sender, recipient, tx) = pending_transfer().await;
+        let other = manager.create_transaction(&sender, recipient.address.clone(), 2.0).await.unwrap();
+
+        let labelled = manager.set_transaction_category(tx.id, Some("income".to_string())).await.unwrap();
+        assert!(signature_valid(&labelled));
+
+        let income = manager.transactions_by_category(&recipient.address, "income").await.unwrap();
+        assert_eq!(income.iter().map(|t| t.id).collect::<Vec<_>>(), vec![tx.id]);
+        assert!(manager.transactions_by_category(&sender.address, "fees").await.unwrap().is_empty());
+
+        manager.set_transaction_category(tx.id, None).await.unwrap();
+        assert!(manager.transactions_by_category(&recipient.address, "income").await.unwrap().is_empty());
+        assert_eq!(manager.find_transaction(other.id).await.unwrap().category, None);
+        assert!(matches!(
+            manager.set_transaction_category(Uuid::new_v4(), Some("x".to_string())).await,
+            Err(CryptoNodeError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn get_or_create_returns_the_oldest_wallet_of_the_currency() {
+        let manager = WalletManager::new();
+        let created = manager.get_or_create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+
+        assert_eq!(manager.get_or_create_wallet(CurrencyType::Bitcoin).await.unwrap().id, created.id);
+        let ethereum = manager.get_or_create_wallet(CurrencyType::Ethereum).await.unwrap();
+        assert_eq!(ethereum.currency_type, CurrencyType::Ethereum);
+        assert_eq!(manager.list_wallets().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn concurrent_get_or_create_makes_one_wallet() {
+        let manager = Arc::new(WalletManager::new());
+        let callers: Vec<_> = (0..8).map(|_| {
+            let manager = manager.clone();
+            tokio::spawn(async move { manager.get_or_create_wallet(CurrencyType::Bitcoin).await.unwrap().id })
+        }).collect();
+
+        let mut ids = Vec::new();
+        for caller in callers {
+            ids.push(caller.await.unwrap());
+        }
+
+        ids.dedup();
+        assert_eq!(ids.len(), 1);
+        assert_eq!(manager.list_wallets().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn wallet_summary_counts_history_by_status() {
+        let (manager, sender, recipient, tx) = pending_transfer().await;
+        let failed = manager.create_transaction(&sender, recipient.address.clone(), 1.0).await.unwrap();
+        manager.update_transaction_status(failed.id, TransactionStatus::Failed).await.unwrap();
+        manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await.unwrap();
+        let latest = manager.create_transaction(&sender, recipient.address.clone(), 0.5).await.unwrap();
+
+        let summary = manager.wallet_summary(sender.id).await.unwrap();
+
+        assert_eq!((summary.pending, summary.confirmed, summary.failed), (1, 1, 1));
+        assert_eq!(summary.last_activity, Some(latest.timestamp));
+        assert_eq!(summary.balance, manager.get_wallet(sender.id).await.unwrap().balance);
+        assert!(summary.age >= chrono::Duration::zero());
+
+        let idle = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let summary = manager.wallet_summary(idle.id).await.unwrap();
+        assert_eq!(summary.last_activity, None);
+        assert_eq!((summary.pending, summary.confirmed, summary.failed), (0, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn addresses_match_regardless_of_hex_case() {
+        let (manager, sender) = funded_wallet(10.0).await;
+        let recipient = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+
+        let tx = manager.create_transaction(&sender, format!(" {} ", recipient.address.to_uppercase()), 1.0).await.unwrap();
+        assert_eq!(tx.to_wallet, recipient.address);
+
+        let history = manager.get_transaction_history(&recipient.address.to_uppercase()).await.unwrap();
+        assert_eq!(history.iter().map(|t| t.id).collect::<Vec<_>>(), vec![tx.id]);
+
+        // Mixed-case records from elsewhere still settle against the right wallets
+        {
+            let mut transactions = manager.transactions.write().await;
+            let stored = transactions.get_mut(tx.id).unwrap();
+            stored.to_wallet = recipient.address.to_uppercase();
+        }
+        manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await.unwrap();
+        assert_eq!(manager.get_wallet(recipient.id).await.unwrap().balance, 1.0);
+    }
+
+    /// Returns only zero bytes, like an unseeded RNG
+    struct ZeroEntropy;
+
+    impl crypto::EntropySource for ZeroEntropy {
+        fn fill_bytes(&self, dest: &mut [u8]) -> Result<()> {
+            dest.fill(0);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn wallets_are_not_created_from_degraded_entropy() {
+        let manager = WalletManager::new();
+        manager.set_entropy_source(Arc::new(ZeroEntropy)).await;
+
+        assert!(matches!(manager.create_wallet(CurrencyType::Bitcoin).await, Err(CryptoNodeError::CryptoOperation(_))));
+        assert!(manager.list_wallets().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fees_paid_count_only_confirmed_outgoing_transactions() {
+        let manager = WalletManager::new();
+        let week_ago = Utc::now() - chrono::Duration::days(7);
+        {
+            let mut transactions = manager.transactions.write().await;
+            let confirmed = transaction(TransactionStatus::Confirmed);
+            transactions.record(Transaction { fee: Some(0.1), timestamp: week_ago, ..confirmed.clone() });
+            transactions.record(Transaction { id: Uuid::new_v4(), fee: Some(0.2), ..confirmed.clone() });
+            transactions.record(Transaction { id: Uuid::new_v4(), fee: Some(0.5), ..transaction(TransactionStatus::Pending) });
+            transactions.record(Transaction {
+                id: Uuid::new_v4(),
+                from_wallet: "bb".to_string(),
+                to_wallet: "aa".to_string(),
+                fee: Some(0.3),
+                ..confirmed
+            });
+        }
+
+        assert!((manager.total_fees_paid("AA", None).await.unwrap() - 0.3).abs() < 1e-9);
+        let since = Utc::now() - chrono::Duration::days(1);
+        assert!((manager.total_fees_paid("aa", Some(since)).await.unwrap() - 0.2).abs() < 1e-9);
+        assert_eq!(manager.total_fees_paid("cc", None).await.unwrap(), 0.0);
+    }
+
+    /// Log output shared with the test that installed the subscriber
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn status_transitions_are_logged_with_their_trigger() {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let confirmed = transaction(TransactionStatus::Confirmed);
+
+        tracing::subscriber::with_default(subscriber, || {
+            log_transition(&confirmed, TransactionStatus::Pending, "merge");
+            log_transition(&confirmed, TransactionStatus::Confirmed, "status_update");
+        });
+
+        let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.contains(&format!("transaction_id={}", confirmed.id)));
+        assert!(output.contains("from=Pending to=Confirmed"));
+        assert!(output.contains("trigger=\"merge\""));
+        assert!(!output.contains("amount"));
+    }
+
+    #[tokio::test]
+    async fn pending_incoming_lists_only_wallets_with_funds_on_the_way() {
+        let (manager, sender, recipient, tx) = pending_transfer().await;
+        manager.set_fee_model(CurrencyType::Bitcoin, FeeModel::DeductedFromAmount).await;
+        manager.create_transaction(&sender, recipient.address.clone(), 2.0).await.unwrap();
+
+        let incoming = manager.wallets_with_pending_incoming().await;
+        assert_eq!(incoming.len(), 1);
+        assert_eq!(incoming[0].0, recipient.id);
+        assert!((incoming[0].1 - (3.0 - 2.0 * ESTIMATED_FEE)).abs() < 1e-9);
+
+        manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await.unwrap();
+        let incoming = manager.wallets_with_pending_incoming().await;
+        assert!((incoming[0].1 - (2.0 - ESTIMATED_FEE)).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn private_keys_leave_memory_only_in_encrypted_backups() {
+        let dir = temp_dir();
+        let path = dir.join("wallets.backup");
+        let source = passphrase_file(&dir);
+        let (manager, wallet) = funded_wallet(1.0).await;
+        let key_hex = hex::encode(&wallet.private_key);
+
+        assert!(!serde_json::to_string(&wallet).unwrap().contains(&key_hex));
+        let loaded: Wallet = serde_json::from_str(&serde_json::to_string(&wallet).unwrap()).unwrap();
+        assert!(loaded.private_key.is_empty());
+
+        write_backup(&path, &source, &manager.wallets, &manager.transactions).await.unwrap();
+        let on_disk = std::fs::read(&path).unwrap();
+        assert!(!on_disk.windows(key_hex.len()).any(|w| w == key_hex.as_bytes()));
+        let restored = storage::restore_backup(&path, &source.resolve().unwrap()).unwrap();
+        assert_eq!(restored.wallets[0].private_key, wallet.private_key);
+    }
+
+    #[tokio::test]
+    async fn balance_at_replays_confirmed_history_up_to_the_moment() {
+        let manager = WalletManager::new();
+        let now = Utc::now();
+        let days_ago = |days| now - chrono::Duration::days(days);
+        {
+            let mut transactions = manager.transactions.write().await;
+            let incoming = Transaction { from_wallet: "bb".to_string(), to_wallet: "aa".to_string(), ..transaction(TransactionStatus::Confirmed) };
+            transactions.record(Transaction { amount: 5.0, timestamp: days_ago(3), ..incoming.clone() });
+            transactions.record(Transaction {
+                id: Uuid::new_v4(),
+                fee: Some(0.1),
+                timestamp: days_ago(2),
+                ..transaction(TransactionStatus::Confirmed)
+            });
+            transactions.record(Transaction { id: Uuid::new_v4(), amount: 9.0, timestamp: days_ago(1), status: TransactionStatus::Pending, ..incoming });
+            transactions.record(Transaction { id: Uuid::new_v4(), timestamp: now, ..transaction(TransactionStatus::Confirmed) });
+        }
+
+        assert_eq!(manager.balance_at("aa", days_ago(4)).await.unwrap(), 0.0);
+        assert_eq!(manager.balance_at("aa", days_ago(3)).await.unwrap(), 5.0);
+        assert!((manager.balance_at("aa", days_ago(1)).await.unwrap() - 3.9).abs() < 1e-9);
+        assert!((manager.balance_at("aa", now).await.unwrap() - 2.9).abs() < 1e-9);
+        assert!((manager.balance_at("bb", now).await.unwrap() + 3.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn transactions_with_covers_both_directions_oldest_first() {
+        let manager = WalletManager::new();
+        let now = Utc::now();
+        let sent = Transaction { timestamp: now, ..transaction(TransactionStatus::Confirmed) };
+        let received = Transaction {
+            id: Uuid::new_v4(),
+            from_wallet: "BB".to_string(),
+            to_wallet: "aa".to_string(),
+            timestamp: now - chrono::Duration::hours(1),
+            ..transaction(TransactionStatus::Pending)
+        };
+        let unrelated = Transaction { id: Uuid::new_v4(), to_wallet: "cc".to_string(), ..transaction(TransactionStatus::Confirmed) };
+        {
+            let mut transactions = manager.transactions.write().await;
+            for tx in [&sent, &received, &unrelated] {
+                transactions.record(tx.clone());
+            }
+        }
+
+        let between = manager.transactions_with("aa", "bb").await.unwrap();
+        assert_eq!(between.iter().map(|t| t.id).collect::<Vec<_>>(), vec![received.id, sent.id]);
+        assert!(manager.transactions_with("bb", "cc").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn orphaned_transactions_are_found_and_removed() {
+        let (manager, _sender, _recipient, tx) = pending_transfer().await;
+        let orphan = transaction(TransactionStatus::Confirmed);
+        manager.transactions.write().await.record(orphan.clone());
+
+        assert_eq!(manager.find_orphaned_transactions().await, vec![orphan.id]);
+
+        let removed = manager.remove_orphaned_transactions().await.unwrap();
+        assert_eq!(removed, vec![orphan.id]);
+        assert!(manager.find_orphaned_transactions().await.is_empty());
+        let history = manager.get_transaction_history(&tx.from_wallet).await.unwrap();
+        assert!(history.iter().any(|t| t.id == tx.id)); // transfers between our wallets stay
+    }
+
+    #[tokio::test]
+    async fn both_algorithms_sign_and_verify() {
+        let manager = WalletManager::new();
+        for algorithm in [SigningAlgorithm::Ed25519, SigningAlgorithm::Secp256k1] {
+            let wallet = manager.create_wallet_with_algorithm(CurrencyType::Bitcoin, algorithm).await.unwrap();
+            assert_eq!(wallet.signing_algorithm, algorithm);
+
+            let signature = manager.sign_message(wallet.id, b"hello").await.unwrap();
+            assert!(verify_message(&wallet.address, b"hello", &signature).unwrap());
+            assert!(!verify_message(&wallet.address, b"other", &signature).unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn new_wallets_follow_the_currency_algorithm() {
+        let manager = WalletManager::new();
+        let wallet = manager.create_wallet(CurrencyType::Ethereum).await.unwrap();
+        assert_eq!(wallet.signing_algorithm, SigningAlgorithm::Secp256k1);
+    }
+
+    #[tokio::test]
+    async fn import_derives_the_address_with_the_given_algorithm() {
+        let key = [7u8; 32];
+        let manager = WalletManager::new();
+        let ed25519 = manager.import_wallet(CurrencyType::Bitcoin, SigningAlgorithm::Ed25519, &key).await.unwrap();
+        let secp256k1 = manager.import_wallet(CurrencyType::Bitcoin, SigningAlgorithm::Secp256k1, &key).await.unwrap();
+
+        assert_eq!(ed25519.public_key, SigningAlgorithm::Ed25519.public_key(&key).unwrap());
+        assert_eq!(secp256k1.public_key, SigningAlgorithm::Secp256k1.public_key(&key).unwrap());
+        assert_ne!(ed25519.address, secp256k1.address);
+    }
+
+    #[tokio::test]
+    async fn queued_transactions_are_stored_in_order() {
+        let (manager, sender) = funded_wallet(10.0).await;
+        let recipient = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        assert!(matches!(manager.set_transaction_queue(Some(0)).await, Err(CryptoNodeError::InvalidInput(_))));
+        manager.set_transaction_queue(Some(4)).await.unwrap();
+
+        let first = manager.create_transaction(&sender, recipient.address.clone(), 1.0).await.unwrap();
+        let second = manager.create_transaction(&sender, recipient.address.clone(), 2.0).await.unwrap();
+
+        let history = manager.get_transaction_history(&sender.address).await.unwrap();
+        let ids: Vec<Uuid> = history.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![first.id, second.id]);
+    }
+
+    #[tokio::test]
+    async fn full_transaction_queue_is_busy() {
+        let (manager, sender) = funded_wallet(10.0).await;
+        let manager = Arc::new(manager);
+        manager.set_transaction_queue(Some(1)).await.unwrap();
+        let queued = || Transaction { id: Uuid::new_v4(), from_wallet: sender.address.clone(), ..transaction(TransactionStatus::Pending) };
+
+        // Holding the store stalls the worker on the batch it has taken
+        let store = manager.transactions.write().await;
+        let taken = tokio::spawn({
+            let (manager, tx) = (manager.clone(), queued());
+            async move { manager.store_transaction(tx).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let waiting = tokio::spawn({
+            let (manager, tx) = (manager.clone(), queued());
+            async move { manager.store_transaction(tx).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let result = manager.store_transaction(queued()).await;
+        assert!(matches!(result, Err(CryptoNodeError::ResourceBusy(_))));
+
+        drop(store);
+        assert!(taken.await.unwrap().is_ok());
+        assert!(waiting.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn balance_view_reflects_the_wallet() {
+        let (manager, wallet) = funded_wallet(3.5).await;
+
+        let view = manager.balance_view(wallet.id).await.unwrap();
+        assert_eq!(view, BalanceView {
+            id: wallet.id,
+            currency: CurrencyType::Bitcoin,
+            balance: 3.5,
+            last_updated: wallet.last_updated,
+        });
+        assert!(matches!(manager.balance_view(Uuid::new_v4()).await, Err(CryptoNodeError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn balance_cap_rejects_updates_and_confirmations() {
+        let (manager, _sender, recipient, tx) = pending_transfer().await;
+        assert!(manager.set_max_balance(Some(0.0)).await.is_err());
+        assert!(manager.set_max_balance(Some(f64::INFINITY)).await.is_err());
+        manager.set_max_balance(Some(10.5)).await.unwrap();
+
+        assert!(matches!(manager.update_wallet_balance(recipient.id, 11.0).await, Err(CryptoNodeError::InvalidInput(_))));
+        assert!(matches!(manager.update_wallet_balance(recipient.id, f64::NAN).await, Err(CryptoNodeError::InvalidInput(_))));
+        manager.update_wallet_balance(recipient.id, 10.0).await.unwrap();
+
+        let result = manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await;
+        assert!(matches!(result, Err(CryptoNodeError::InvalidInput(_))));
+        let stored = manager.transactions.read().await.get(tx.id).cloned().unwrap();
+        assert_eq!(stored.status, TransactionStatus::Pending); // untouched by the rejected credit
+        assert_eq!(manager.get_wallet(recipient.id).await.unwrap().balance, 10.0);
+
+        manager.set_max_balance(None).await.unwrap();
+        manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await.unwrap();
+        assert_eq!(manager.get_wallet(recipient.id).await.unwrap().balance, 11.0);
+    }
+
+    #[tokio::test]
+    async fn payments_to_derived_addresses_credit_the_wallet() {
+        let (manager, sender) = funded_wallet(10.0).await;
+        let recipient = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let first = manager.next_receive_address(recipient.id).await.unwrap();
+        let second = manager.next_receive_address(recipient.id).await.unwrap();
+        assert_ne!(first, second);
+        assert_eq!(manager.get_wallet(recipient.id).await.unwrap().receive_addresses, vec![first, second.clone()]);
+
+        let tx = manager.create_transaction(&sender, second, 2.0).await.unwrap();
+        manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await.unwrap();
+        assert_eq!(manager.get_wallet(recipient.id).await.unwrap().balance, 2.0);
+        assert!(manager.find_orphaned_transactions().await.is_empty());
+    }
+
+    /// Exchange at a fixed rate, signing payouts with a secp256k1 key
+    struct FakeExchange {
+        rate: f64,
+        fail: bool,
+        executed: std::sync::atomic::AtomicUsize,
+    }
+
+    impl FakeExchange {
+        const KEY: [u8; 32] = [7; 32];
+
+        fn new(rate: f64, fail: bool) -> Self {
+            Self { rate, fail, executed: std::sync::atomic::AtomicUsize::new(0) }
+        }
+
+        fn executions(&self) -> usize {
+            self.executed.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    impl ExchangeBackend for FakeExchange {
+        fn address(&self) -> String {
+            hex::encode(SigningAlgorithm::Secp256k1.public_key(&Self::KEY).unwrap())
+        }
+
+        fn quote(&self, _from: &CurrencyType, _to: &CurrencyType, amount: f64) -> Result<f64> {
+            Ok(amount * self.rate)
+        }
+
+        fn execute(&self, from: &CurrencyType, to: &CurrencyType, amount: f64) -> Result<f64> {
+            self.executed.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if self.fail {
+                return Err(CryptoNodeError::Network(NetworkFailure::new("exchange", Some(503), "exchange unavailable")));
+            }
+            self.quote(from, to, amount)
+        }
+
+        fn sign(&self, payload: &[u8]) -> Result<Vec<u8>> {
+            SigningAlgorithm::Secp256k1.sign(&Self::KEY, payload)
+        }
+    }
+
+    /// A funded Bitcoin wallet and an empty Ethereum wallet
+    async fn exchange_wallets() -> (WalletManager, Wallet, Wallet) {
+        let (manager, source) = funded_wallet(10.0).await;
+        let destination = manager.create_wallet(CurrencyType::Ethereum).await.unwrap();
+        (manager, source, destination)
+    }
+
+    #[tokio::test]
+    async fn exchange_transfer_signs_and_confirms_both_legs() {
+        let (manager, source, destination) = exchange_wallets().await;
+        let exchange = FakeExchange::new(20.0, false);
+
+        let (debit, credit) = manager.transfer_between_currencies(source.id, destination.id, 2.0, &exchange)
+            .await.unwrap();
+
+        assert_eq!(debit.status, TransactionStatus::Confirmed);
+        assert_eq!(credit.status, TransactionStatus::Confirmed);
+        assert!(debit.signature.is_some() && credit.signature.is_some());
+        assert!(manager.verify_all_signatures().await.unwrap().is_empty());
+        assert_eq!(manager.get_wallet(source.id).await.unwrap().balance, 8.0);
+        assert_eq!(manager.get_wallet(destination.id).await.unwrap().balance, 40.0);
+    }
+
+    #[tokio::test]
+    async fn exchange_transfer_over_cap_never_executes() {
+        let (manager, source, destination) = exchange_wallets().await;
+        manager.set_max_balance(Some(30.0)).await.unwrap();
+        let exchange = FakeExchange::new(20.0, false);
+
+        let result = manager.transfer_between_currencies(source.id, destination.id, 2.0, &exchange).await;
+
+        assert!(result.is_err());
+        assert_eq!(exchange.executions(), 0);
+        assert!(manager.get_transaction_history(&source.address).await.unwrap().is_empty());
+        assert_eq!(manager.get_wallet(source.id).await.unwrap().balance, 10.0);
+    }
+
+    #[tokio::test]
+    async fn exchange_transfer_failure_marks_debit_failed() {
+        let (manager, source, destination) = exchange_wallets().await;
+        let exchange = FakeExchange::new(20.0, true);
+
+        let result = manager.transfer_between_currencies(source.id, destination.id, 2.0, &exchange).await;
+
+        assert!(matches!(result, Err(CryptoNodeError::Network(_))));
+        let history = manager.get_transaction_history(&source.address).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].status, TransactionStatus::Failed);
+        assert_eq!(manager.get_wallet(source.id).await.unwrap().balance, 10.0);
+        assert_eq!(manager.get_wallet(destination.id).await.unwrap().balance, 0.0);
+    }
+
+    #[tokio::test]
+    async fn exchange_transfer_rejects_more_than_spendable() {
+        let (manager, source, destination) = exchange_wallets().await;
+        let exchange = FakeExchange::new(1.0, false);
+
+        let result = manager.transfer_between_currencies(source.id, destination.id, 11.0, &exchange).await;
+
+        assert!(matches!(result, Err(CryptoNodeError::InsufficientBalance(_))));
+        assert_eq!(exchange.executions(), 0);
+    }
+
+    #[tokio::test]
+    async fn mutations_after_shutdown_are_cancelled() {
+        let (manager, sender, recipient, tx) = pending_transfer().await;
+        manager.shutdown().await.unwrap();
+
+        let cancelled = |result: Result<()>| matches!(result, Err(CryptoNodeError::Cancelled));
+        assert!(cancelled(manager.create_transaction(&sender, recipient.address.clone(), 1.0).await.map(drop)));
+        assert!(cancelled(manager.update_transaction_status(tx.id, TransactionStatus::Confirmed).await.map(drop)));
+        assert!(cancelled(manager.update_wallet_balance(sender.id, 1.0).await.map(drop)));
+        assert!(cancelled(manager.next_receive_address(recipient.id).await.map(drop)));
+        assert!(cancelled(manager.remove_orphaned_transactions().await.map(drop)));
+
+        // Queries still see the state as it was
+        assert_eq!(manager.get_wallet(sender.id).await.unwrap().balance, 10.0);
+        assert_eq!(manager.get_transaction_history(&sender.address).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn non_finite_amounts_are_rejected_on_load() {
+        let (manager, sender, _recipient, tx) = pending_transfer().await;
+        let corrupted = Transaction { amount: f64::NAN, ..transaction(TransactionStatus::Confirmed) };
+        assert!(matches!(manager.merge_history(vec![corrupted.clone()]).await, Err(CryptoNodeError::InvalidInput(_))));
+        assert_eq!(manager.get_transaction_history("aa").await.unwrap().len(), 0);
+
+        let mut snapshot = manager.snapshot().await;
+        snapshot.wallets.get_mut(&sender.id).unwrap().balance = f64::INFINITY;
+        assert!(matches!(manager.restore(snapshot).await, Err(CryptoNodeError::InvalidInput(_))));
+
+        let mut snapshot = manager.snapshot().await;
+        snapshot.transactions.push(Transaction { fee: Some(-1.0), ..tx });
+        assert!(matches!(manager.restore(snapshot).await, Err(CryptoNodeError::InvalidInput(_))));
+        assert_eq!(manager.get_wallet(sender.id).await.unwrap().balance, 10.0);
+    }
+
+    #[tokio::test]
+    async fn persisted_state_loads_back() {
+        let dir = temp_dir();
+        let path = dir.join("wallets.dat");
+        let manager = WalletManager::load_from_disk(&path, passphrase_file(&dir)).await.unwrap();
+        let sender = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let sender = manager.update_wallet_balance(sender.id, 5.0).await.unwrap();
+        let recipient = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        let tx = manager.create_transaction(&sender, recipient.address.clone(), 1.0).await.unwrap();
+        manager.persist().await.unwrap();
+
+        let loaded = WalletManager::load_from_disk(&path, passphrase_file(&dir)).await.unwrap();
+        let loaded_sender = loaded.get_wallet(sender.id).await.unwrap();
+        assert_eq!(loaded.list_wallets().await.unwrap().len(), 2);
+        assert_eq!(loaded_sender.private_key, sender.private_key);
+        assert_eq!(loaded_sender.balance, 5.0);
+        assert_eq!(loaded.find_transaction(tx.id).await.unwrap().signature, tx.signature);
+    }
+
+    #[tokio::test]
+    async fn persist_requires_a_data_file() {
+        let manager = WalletManager::new();
+        assert!(matches!(manager.persist().await, Err(CryptoNodeError::Storage(_))));
+    }
+
+    #[tokio::test]
+    async fn persist_hands_the_write_to_the_backup_task_for_its_file() {
+        let dir = temp_dir();
+        let path = dir.join("wallets.dat");
+        let source = passphrase_file(&dir);
+        let manager = WalletManager::load_from_disk(&path, source.clone()).await.unwrap();
+        manager.configure_auto_backup(AutoBackupConfig {
+            enabled: true,
+            path: path.clone(),
+            passphrase_source: source.clone(),
+            debounce_secs: 60,
+            max_batch: 100,
+            max_delay_secs: 60,
+        }).await;
+        manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        assert_eq!(manager.pending_changes.load(Ordering::SeqCst), 1);
+
+        manager.persist().await.unwrap();
+
+        // Only the backup task clears its pending batch
+        assert_eq!(manager.pending_changes.load(Ordering::SeqCst), 0);
+        assert_eq!(storage::restore_backup(&path, &source.resolve().unwrap()).unwrap().wallets.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn freeze_survives_reload() {
+        let (manager, path, source) = batched_backups(2).await;
+        let wallet = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        manager.freeze_wallet(wallet.id).await.unwrap();
+        timeout(Duration::from_secs(2), async {
+            while !path.exists() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.expect("freeze never marked state dirty");
+
+        let loaded = WalletManager::load_from_disk(&path, source).await.unwrap();
+        assert!(loaded.get_wallet(wallet.id).await.unwrap().frozen);
+    }
+
+    #[tokio::test]
+    async fn created_transactions_are_signed_by_the_sender() {
+        let manager = WalletManager::new();
+        let recipient = manager.create_wallet(CurrencyType::Bitcoin).await.unwrap();
+        for algorithm in [SigningAlgorithm::Ed25519, SigningAlgorithm::Secp256k1] {
+            let sender = manager.create_wallet_with_algorithm(CurrencyType::Bitcoin, algorithm).await.unwrap();
+            let sender = manager.update_wallet_balance(sender.id, 10.0).await.unwrap();
+
+            let tx = manager.create_transaction(&sender, recipient.address.clone(), 1.0).await.unwrap();
+            assert!(manager.verify_transaction(&tx, &sender.public_key).unwrap());
+            assert!(!manager.verify_transaction(&tx, &recipient.public_key).unwrap());
+
+            let tampered = Transaction { amount: 2.0, ..tx.clone() };
+            assert!(!manager.verify_transaction(&tampered, &sender.public_key).unwrap_or(false));
+            let unsigned = Transaction { signature: None, ..tx };
+            assert!(!manager.verify_transaction(&unsigned, &sender.public_key).unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn signing_refuses_another_wallets_transaction() {
+        let (manager, _sender, recipient, mut tx) = pending_transfer().await;
+        assert!(matches!(manager.sign_transaction(&recipient, &mut tx), Err(CryptoNodeError::InvalidInput(_))));
+    }
+}